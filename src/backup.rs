@@ -0,0 +1,135 @@
+//! Zip archives of world folders, used for backups.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Writes the folders named in `folders`, found in `base`, into a new zip archive at `archive`.
+/// Entries are stored relative to `base`, e.g. `world/level.dat`
+pub(crate) fn write_archive(base: &Path, folders: &[String], archive: &Path) -> io::Result<()> {
+  let mut writer = ZipWriter::new(File::create(archive)?);
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+  for folder in folders {
+    add_folder(&mut writer, &base.join(folder), folder, options)?;
+  }
+  writer.finish()?;
+  Ok(())
+}
+
+fn add_folder(writer: &mut ZipWriter<File>, path: &Path, name: &str, options: SimpleFileOptions) -> io::Result<()> {
+  writer.add_directory(name, options)?;
+  for entry in fs::read_dir(path)? {
+    let entry = entry?;
+    let entry_name = format!("{}/{}", name, entry.file_name().to_string_lossy());
+    if entry.file_type()?.is_dir() {
+      add_folder(writer, &entry.path(), &entry_name, options)?;
+    } else {
+      writer.start_file(entry_name, options)?;
+      io::copy(&mut File::open(entry.path())?, writer)?;
+    }
+  }
+  Ok(())
+}
+
+/// Unpacks `archive` into `base`, first removing every top level folder the archive contains so
+/// they end up exactly as archived. Every entry has to be inside one of the folders named in
+/// `allowed`, otherwise nothing is extracted
+pub(crate) fn extract_archive(archive: &Path, base: &Path, allowed: &[String]) -> io::Result<()> {
+  let mut archive = ZipArchive::new(File::open(archive)?)?;
+  let mut folders = HashSet::new();
+  for i in 0..archive.len() {
+    let entry = archive.by_index(i)?;
+    //Entries that would land outside of base are refused rather than skipped
+    let name = entry
+      .enclosed_name()
+      .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "archive entry escapes the server folder"))?;
+    let mut components = name.components();
+    //A file can't stand in for a folder, e.g. an entry named just `world`
+    let folder = match (components.next(), components.next().is_some() || entry.is_dir()) {
+      (Some(Component::Normal(folder)), true) if allowed.iter().any(|allowed| folder == allowed.as_str()) => folder,
+      _ => {
+        return Err(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("archive entry {} isn't inside a world folder", name.display()),
+        ))
+      }
+    };
+    folders.insert(folder.to_os_string());
+  }
+  for folder in &folders {
+    let path = base.join(folder);
+    if path.is_dir() {
+      fs::remove_dir_all(path)?;
+    }
+  }
+  for i in 0..archive.len() {
+    let mut file = archive.by_index(i)?;
+    let path = match file.enclosed_name() {
+      Some(name) => base.join(name),
+      None => continue,
+    };
+    if file.is_dir() {
+      fs::create_dir_all(&path)?;
+    } else {
+      if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+      }
+      io::copy(&mut file, &mut File::create(&path)?)?;
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn archives_round_trip() {
+    let base = std::env::temp_dir().join(format!("serbo-backup-{}", std::process::id()));
+    let region = base.join("world").join("region");
+    fs::create_dir_all(&region).unwrap();
+    let level: Vec<u8> = (0..=255).collect();
+    let chunks: Vec<u8> = (0..64 * 1024).map(|i| (i * 7 % 251) as u8).collect();
+    fs::write(base.join("world").join("level.dat"), &level).unwrap();
+    fs::write(region.join("r.0.0.mca"), &chunks).unwrap();
+    let archive = base.join("backup.zip");
+    write_archive(&base, &[String::from("world")], &archive).unwrap();
+    //Changes made after the backup should be undone by restoring it
+    fs::write(base.join("world").join("level.dat"), b"corrupted").unwrap();
+    fs::remove_file(region.join("r.0.0.mca")).unwrap();
+    fs::write(region.join("r.1.0.mca"), b"newer").unwrap();
+    extract_archive(&archive, &base, &[String::from("world"), String::from("world_nether")]).unwrap();
+    assert_eq!(fs::read(base.join("world").join("level.dat")).unwrap(), level);
+    assert_eq!(fs::read(region.join("r.0.0.mca")).unwrap(), chunks);
+    assert!(!region.join("r.1.0.mca").exists());
+    fs::remove_dir_all(&base).unwrap();
+  }
+
+  #[test]
+  fn entries_outside_the_world_folders_are_refused() {
+    let base = std::env::temp_dir().join(format!("serbo-restore-{}", std::process::id()));
+    fs::create_dir_all(&base).unwrap();
+    fs::write(base.join("ops.json"), "[]").unwrap();
+    let worlds = [String::from("world"), String::from("world_nether"), String::from("world_the_end")];
+    for (i, name) in ["ops.json", "server.jar", "world", "plugins/evil.jar", "world_old/level.dat"].iter().enumerate() {
+      let archive = base.join(format!("crafted-{}.zip", i));
+      let mut writer = ZipWriter::new(File::create(&archive).unwrap());
+      writer.start_file("world/level.dat", SimpleFileOptions::default()).unwrap();
+      io::Write::write_all(&mut writer, b"level").unwrap();
+      writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+      io::Write::write_all(&mut writer, b"replaced").unwrap();
+      writer.finish().unwrap();
+      let error = extract_archive(&archive, &base, &worlds).unwrap_err();
+      assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{}", name);
+    }
+    //Nothing is touched when an archive is refused
+    assert_eq!(fs::read_to_string(base.join("ops.json")).unwrap(), "[]");
+    assert!(!base.join("world").exists());
+    fs::remove_dir_all(&base).unwrap();
+  }
+}
@@ -0,0 +1,71 @@
+//! The queue of console commands waiting to be written to a server's stdin.
+
+use std::sync::{Condvar, Mutex, RwLock};
+
+use crate::{validate_command, Result};
+
+/// Commands waiting for the stdin writer thread, which sleeps until some are pushed
+#[derive(Debug, Default)]
+pub(crate) struct CommandQueue {
+  commands: Mutex<Vec<String>>,
+  pushed: Condvar,
+}
+
+impl CommandQueue {
+  pub(crate) fn new() -> CommandQueue {
+    CommandQueue::default()
+  }
+  /// Queues a single command
+  pub(crate) fn push(&self, command: String) -> Result<()> {
+    validate_command(&command)?;
+    self.commands.lock().unwrap().push(command);
+    self.pushed.notify_all();
+    Ok(())
+  }
+  /// Queues every command in `commands` under one lock, or none of them if any isn't valid
+  pub(crate) fn push_all(&self, commands: &[String]) -> Result<()> {
+    for command in commands {
+      validate_command(command)?;
+    }
+    self.commands.lock().unwrap().extend_from_slice(commands);
+    self.pushed.notify_all();
+    Ok(())
+  }
+  /// Wakes the writer so it notices `running` has changed
+  pub(crate) fn wake(&self) {
+    //Taking the lock means the writer is either waiting or yet to check running, so it can't miss this
+    let _commands = self.commands.lock().unwrap();
+    self.pushed.notify_all();
+  }
+  /// Waits for commands to be queued and takes all of them. Returns None once `running` is false
+  /// and the queue is empty, so commands queued before shutting down are still written
+  pub(crate) fn next_batch(&self, running: &RwLock<bool>) -> Option<Vec<String>> {
+    let mut commands = self.commands.lock().unwrap();
+    loop {
+      if !commands.is_empty() {
+        return Some(commands.drain(..).collect());
+      }
+      if !*running.read().unwrap() {
+        return None;
+      }
+      commands = self.pushed.wait(commands).unwrap();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn push_all_queues_nothing_if_one_command_is_bad() {
+    let queue = CommandQueue::new();
+    let batch = [String::from("op Steve"), String::from("say hi\nop me"), String::from("list")];
+    assert!(queue.push_all(&batch).is_err());
+    assert!(queue.push(String::from("a\rb")).is_err());
+    assert!(queue.commands.lock().unwrap().is_empty());
+    queue.push_all(&[String::from("op Steve"), String::from("list")]).unwrap();
+    assert_eq!(queue.next_batch(&RwLock::new(true)).unwrap(), vec!["op Steve", "list"]);
+    assert_eq!(queue.next_batch(&RwLock::new(false)), None);
+  }
+}
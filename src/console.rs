@@ -0,0 +1,155 @@
+//! The bounded buffer a server's output lines are kept in, and its optional copy on disk.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A ring buffer of output lines that are addressed by their absolute line number.
+///
+/// Line numbers count every line ever pushed, so they keep pointing at the same line after older
+/// lines have been evicted to stay within `capacity`.
+#[derive(Debug)]
+pub(crate) struct LogBuffer {
+  lines: VecDeque<String>,
+  /// The line number of the oldest line still held
+  first_line_offset: usize,
+  capacity: usize,
+}
+
+impl LogBuffer {
+  pub(crate) fn new(capacity: usize) -> LogBuffer {
+    LogBuffer {
+      lines: VecDeque::new(),
+      first_line_offset: 0,
+      capacity,
+    }
+  }
+  /// Appends a line, returning the oldest line if it had to be evicted to make room
+  pub(crate) fn push(&mut self, line: String) -> Option<String> {
+    let evicted = if self.lines.len() >= self.capacity {
+      self.first_line_offset += 1;
+      self.lines.pop_front()
+    } else {
+      None
+    };
+    self.lines.push_back(line);
+    evicted
+  }
+  /// The line number of the oldest line still held
+  pub(crate) fn first_line(&self) -> usize {
+    self.first_line_offset
+  }
+  /// The line number the next pushed line will get, i.e. the number of lines pushed so far
+  pub(crate) fn end(&self) -> usize {
+    self.first_line_offset + self.lines.len()
+  }
+  /// Iterates over the held lines from line number `start` onwards. Evicted lines are skipped
+  pub(crate) fn lines_from(&self, start: usize) -> impl Iterator<Item = &String> {
+    let skip = start.saturating_sub(self.first_line_offset);
+    self.lines.iter().skip(skip)
+  }
+  /// Copies the held lines from line number `start` onwards. Evicted lines are skipped
+  pub(crate) fn get(&self, start: usize) -> Vec<String> {
+    self.lines_from(start).cloned().collect()
+  }
+}
+
+/// The name of the file console lines are copied to, inside a server's logs folder
+pub(crate) const LOG_FILE_NAME: &str = "serbo-console.log";
+
+/// A copy of the console log on disk, rotated so it doesn't grow without bound.
+///
+/// Once the file holds `max_lines` lines it is renamed to `serbo-console.log.1`, replacing the
+/// previous one, and a new file is started. Between them the two files always hold at least the
+/// last `max_lines` lines.
+#[derive(Debug)]
+pub(crate) struct LogFile {
+  path: PathBuf,
+  file: LineWriter<File>,
+  lines: usize,
+  max_lines: usize,
+}
+
+impl LogFile {
+  /// Opens the log file in `folder` for appending, creating the folder if needed
+  pub(crate) fn open(folder: &Path, max_lines: usize) -> io::Result<LogFile> {
+    fs::create_dir_all(folder)?;
+    let path = folder.join(LOG_FILE_NAME);
+    let lines = match File::open(&path) {
+      Ok(file) => BufReader::new(file).lines().count(),
+      Err(_) => 0,
+    };
+    let file = LineWriter::new(OpenOptions::new().create(true).append(true).open(&path)?);
+    Ok(LogFile {
+      path,
+      file,
+      lines,
+      max_lines,
+    })
+  }
+  /// Appends a line, rotating the file first if it is full
+  pub(crate) fn write_line(&mut self, line: &str) -> io::Result<()> {
+    if self.lines >= self.max_lines {
+      self.file.flush()?;
+      fs::rename(&self.path, rotated_path(&self.path))?;
+      self.file = LineWriter::new(File::create(&self.path)?);
+      self.lines = 0;
+    }
+    writeln!(self.file, "{}", line)?;
+    self.lines += 1;
+    Ok(())
+  }
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+  let mut rotated = path.as_os_str().to_os_string();
+  rotated.push(".1");
+  PathBuf::from(rotated)
+}
+
+/// Reads the last `count` lines from the log file in `folder` and the one rotated out before it
+pub(crate) fn read_log_tail(folder: &Path, count: usize) -> io::Result<Vec<String>> {
+  let path = folder.join(LOG_FILE_NAME);
+  let mut tail = VecDeque::new();
+  if count == 0 {
+    return Ok(Vec::new());
+  }
+  for file in &[rotated_path(&path), path] {
+    let file = match File::open(file) {
+      Ok(file) => file,
+      Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
+      Err(e) => return Err(e),
+    };
+    for line in BufReader::new(file).lines() {
+      if tail.len() == count {
+        tail.pop_front();
+      }
+      tail.push_back(line?);
+    }
+  }
+  Ok(tail.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::LogBuffer;
+
+  #[test]
+  fn log_buffer_numbers_lines_past_capacity() {
+    let mut buffer = LogBuffer::new(3);
+    for i in 0..3 {
+      assert_eq!(buffer.push(format!("line {}", i)), None);
+    }
+    assert_eq!(buffer.first_line(), 0);
+    assert_eq!(buffer.push(String::from("line 3")), Some(String::from("line 0")));
+    assert_eq!(buffer.push(String::from("line 4")), Some(String::from("line 1")));
+    assert_eq!(buffer.first_line(), 2);
+    assert_eq!(buffer.end(), 5);
+    assert_eq!(buffer.get(3), vec!["line 3", "line 4"]);
+    assert_eq!(buffer.lines_from(4).collect::<Vec<_>>(), vec!["line 4"]);
+    assert!(buffer.get(5).is_empty());
+    //Lines that were evicted are skipped rather than shifting what is returned
+    assert_eq!(buffer.get(0), vec!["line 2", "line 3", "line 4"]);
+  }
+}
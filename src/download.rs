@@ -0,0 +1,44 @@
+//! Downloading vanilla server jars from Mojang's version manifest.
+
+use std::io::Read;
+
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+
+use crate::{Error, Result};
+
+const VERSION_MANIFEST: &str = "https://launchermeta.mojang.com/mc/game/version_manifest.json";
+
+fn get_json(url: &str) -> Result<Value> {
+  let response = ureq::get(url).call().map_err(|e| Error::DownloadFailed(e.to_string()))?;
+  let body = response.into_string()?;
+  serde_json::from_str(&body).map_err(|e| Error::DownloadFailed(format!("malformed response from {}: {}", url, e)))
+}
+
+/// Downloads the vanilla server jar for `version`, checking it against the hash in the manifest
+pub(crate) fn server_jar(version: &str) -> Result<Vec<u8>> {
+  let manifest = get_json(VERSION_MANIFEST)?;
+  let version_url = manifest["versions"]
+    .as_array()
+    .and_then(|versions| versions.iter().find(|v| v["id"].as_str() == Some(version)))
+    .and_then(|v| v["url"].as_str())
+    .ok_or_else(|| Error::VersionNotFound(version.to_string()))?;
+  let details = get_json(version_url)?;
+  let server = &details["downloads"]["server"];
+  //Versions before 1.2.5 have no server download
+  let (jar_url, sha1) = match (server["url"].as_str(), server["sha1"].as_str()) {
+    (Some(url), Some(sha1)) => (url, sha1),
+    _ => return Err(Error::VersionNotFound(version.to_string())),
+  };
+  let mut jar = Vec::new();
+  ureq::get(jar_url)
+    .call()
+    .map_err(|e| Error::DownloadFailed(e.to_string()))?
+    .into_reader()
+    .read_to_end(&mut jar)?;
+  let digest: String = Sha1::digest(&jar).iter().map(|byte| format!("{:02x}", byte)).collect();
+  if !digest.eq_ignore_ascii_case(sha1) {
+    return Err(Error::ChecksumMismatch(version.to_string()));
+  }
+  Ok(jar)
+}
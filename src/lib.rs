@@ -1,13 +1,12 @@
 //! Allows for simple control, and input / output of minecraft servers.
 //!
 //! # Examples
-//! ```
-//!use serbo;
+//! ```no_run
 //!use std::error::Error;
 //!use std::io;
 //!
 //!fn main() -> Result<(), Box<dyn Error>> {
-//!  let mut manager = serbo::Manager::new();
+//!  let mut manager = serbo::Manager::new("servers", "versions");
 //!  let port = 25565;
 //!  let id = "1";
 //!  loop {
@@ -30,26 +29,26 @@
 //!        send_buf = send_buf[..send_buf.chars().count() - 1].to_string();
 //!        manager.change_version(id, &send_buf)?;
 //!      }
-//!      "create" => match manager.create() {
+//!      "create" => match manager.create(id, &serbo::CreateOptions::new("1.16.1")) {
 //!        Ok(_) => println!("Server Created"),
 //!        Err(e) => println!("{}", e),
 //!      },
 //!      "stop" => {
 //!        //Stops the server
 //!        println!("Server stopping.");
-//!        manager.stop()?;
+//!        manager.stop(id)?;
 //!      }
 //!      "start" => {
 //!        //Starts the server
 //!        println!("Server starting.");
-//!        match manager.start(port) {
+//!        match manager.start(id, port) {
 //!          Err(e) => println!("{}", e),
 //!          Ok(_) => println!("Server started!"),
 //!        };
 //!      }
 //!      "send" => {
 //!        //Prompts for a command to send to the server
-//!        if let Some(instance) = manager.get(){
+//!        if let Some(instance) = manager.get(id){
 //!          let mut send_buf = String::new();
 //!          println!("Enter the command to send to the server.");
 //!          reader.read_line(&mut send_buf)?;
@@ -68,7 +67,7 @@
 //!      }
 //!      "get" => {
 //!        //Gets the last 5 stdout lines
-//!        if let Some(instance) = manager.get(){
+//!        if let Some(instance) = manager.get(id){
 //!          let vec = instance.get(0);
 //!          let length = vec.len();
 //!          //Create a vec from the last 5 lines
@@ -86,6 +85,15 @@
 //!          println!("Server Offline.")
 //!        }
 //!      }
+//!      "exit" => {
+//!        //Stops every server before quitting, so no java process is left behind
+//!        for (id, result) in manager.stop_all() {
+//!          if let Err(e) = result {
+//!            println!("Failed to stop {}: {}", id, e);
+//!          }
+//!        }
+//!        return Ok(());
+//!      }
 //!      _ => {
 //!        println!("Unrecognized command");
 //!      }
@@ -94,15 +102,59 @@
 //!}
 //! ```
 
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::io::{BufRead, BufReader, BufWriter, Write};
-use std::path::Path;
-use std::process::{Child, Command, Stdio};
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::{IpAddr, TcpListener};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+mod backup;
+mod command_queue;
+mod console;
+#[cfg(feature = "download")]
+mod download;
+mod log_line;
+mod metrics;
+mod outcome;
+mod patterns;
+mod ping;
+mod player_list;
+mod properties;
+mod rcon;
+mod resources;
+mod schedule;
+use command_queue::CommandQueue;
+use console::{LogBuffer, LogFile};
+pub use log_line::{LogLevel, LogLine};
+use metrics::Sampler;
+pub use metrics::{MetricsHandle, ServerMetrics};
+pub use outcome::OperationOutcome;
+pub use patterns::ConsolePatterns;
+pub use ping::ServerPing;
+use player_list::PlayerList;
+use properties::{property, PropertiesFile};
+pub use properties::ServerProperties;
+use rcon::RconClient;
+pub use resources::ResourceUsage;
+use schedule::{Schedule, ScheduleConfig, TimerSignals};
+pub use schedule::ScheduleBuilder;
 
 type Result<T> = std::result::Result<T, Error>;
 
+/// Maps a server id to its folder, see [set_path_resolver](struct.Manager.html#method.set_path_resolver)
+type PathResolver = Box<dyn Fn(&str) -> PathBuf + Send + Sync>;
+/// Receivers of [subscribe_all](struct.Manager.html#method.subscribe_all), sent each line with its server's id
+type TaggedSubscribers = Arc<Mutex<Vec<Sender<(String, String)>>>>;
+
 #[derive(Debug)]
 pub enum Error {
   /// Arises when there is an error regarding IO
@@ -111,8 +163,8 @@ pub enum Error {
   ServerOffline(),
   /// Arises when attempting to start a server that is already online
   ServerAlreadyOnline(),
-  /// Arises when a server's files are missing
-  ServerFilesMissing(),
+  /// Arises when a server's files are missing. Contains the path that wasn't found
+  ServerFilesMissing(PathBuf),
   /// Arises when attempting to create a server with the same id as an existing server
   ServerAlreadyExists(),
   /// Arises when there is an error involving a server's stdin/stdout threads
@@ -121,20 +173,104 @@ pub enum Error {
   /// May occur due to the server process being killed, the server crashing or ingame methods
   /// to stop the server
   ServerProcessExited(),
-  ServerStillStarting()
+  /// Arises when an operation needs the server to have finished starting, such as stopping it
+  ServerStillStarting(),
+  /// Arises when the server refuses to run because `eula=true` has not been set in its eula.txt
+  EulaNotAccepted(),
+  /// Arises when a command sent to the server did not report success. Contains the server's
+  /// response, or a note that none arrived in time
+  CommandFailed(String),
+  /// Arises when a key in server.properties holds a value that can't be understood.
+  /// Contains the key
+  InvalidProperty(String),
+  /// Arises when sending to a server whose stdin pipe has been closed, such as partway
+  /// through shutting down
+  StdinClosed(),
+  /// Arises when an operation isn't supported by the server software or the current platform.
+  /// Contains the unsupported operation
+  Unsupported(String),
+  /// Arises when a value passed to a command helper is malformed. Contains a description of the problem
+  InvalidArgument(String),
+  /// Arises when a message sent to the server contains a line break, which would let it run more
+  /// than one command. Contains the message
+  InvalidCommand(String),
+  /// Arises when a command targets a player that isn't on the server. Contains the player's name
+  PlayerNotOnline(String),
+  /// Arises when the server doesn't recognize an item id. Contains the item id
+  UnknownItem(String),
+  /// Arises when RCON is needed but enable-rcon is off in server.properties
+  RconDisabled(),
+  /// Arises when nothing is accepting RCON connections on the configured port
+  RconRefused(),
+  /// Arises when the server rejects the configured RCON password
+  RconAuthFailed(),
+  /// Arises when the RCON connection or a reply takes too long
+  RconTimeout(),
+  /// Arises when the server process exited after the JVM ran out of memory
+  OutOfMemory(),
+  /// Arises when a server's port is already bound, detected before launching or from the server
+  /// failing to bind it. Contains the port
+  PortInUse(u32),
+  /// Arises when every port in the range given to allocate_port is taken
+  NoPortsAvailable(),
+  /// Arises when a server hasn't finished starting within the time it was given
+  StartTimeout(),
+  /// Arises when a server didn't shut down in time after a stop and had to be killed
+  StopTimeout(),
+  /// Arises when fetching a version that Mojang's version manifest doesn't list or has no server
+  /// jar for. Contains the version. Only returned with the `download` feature
+  VersionNotFound(String),
+  /// Arises when a downloaded server jar doesn't match the SHA-1 in the version manifest.
+  /// Contains the version. Only returned with the `download` feature
+  ChecksumMismatch(String),
+  /// Arises when a request to Mojang's servers fails. Contains the reason. Only returned with the
+  /// `download` feature
+  DownloadFailed(String)
 }
 
-impl std::error::Error for Error {
-  fn description(&self) -> &str {
+impl Error {
+  /// The name of the variant, e.g. `ServerOffline`, for telling errors apart without matching on
+  /// them, such as in a JSON response
+  pub fn kind(&self) -> &'static str {
     match *self {
-      Error::IoError(_) => "IOError",
-      Error::ServerFilesMissing() => "MissingServer",
+      Error::IoError(..) => "IoError",
       Error::ServerOffline() => "ServerOffline",
+      Error::ServerAlreadyOnline() => "ServerAlreadyOnline",
+      Error::ServerFilesMissing(..) => "ServerFilesMissing",
       Error::ServerAlreadyExists() => "ServerAlreadyExists",
-      Error::ThreadError(_) => "ThreadError",
+      Error::ThreadError(..) => "ThreadError",
       Error::ServerProcessExited() => "ServerProcessExited",
-      Error::ServerAlreadyOnline() => "ServerAlreadyOnline",
-      Error::ServerStillStarting() => "ServerStillStarting"
+      Error::ServerStillStarting() => "ServerStillStarting",
+      Error::EulaNotAccepted() => "EulaNotAccepted",
+      Error::CommandFailed(..) => "CommandFailed",
+      Error::InvalidProperty(..) => "InvalidProperty",
+      Error::StdinClosed() => "StdinClosed",
+      Error::Unsupported(..) => "Unsupported",
+      Error::InvalidArgument(..) => "InvalidArgument",
+      Error::InvalidCommand(..) => "InvalidCommand",
+      Error::PlayerNotOnline(..) => "PlayerNotOnline",
+      Error::UnknownItem(..) => "UnknownItem",
+      Error::RconDisabled() => "RconDisabled",
+      Error::RconRefused() => "RconRefused",
+      Error::RconAuthFailed() => "RconAuthFailed",
+      Error::RconTimeout() => "RconTimeout",
+      Error::OutOfMemory() => "OutOfMemory",
+      Error::PortInUse(..) => "PortInUse",
+      Error::NoPortsAvailable() => "NoPortsAvailable",
+      Error::StartTimeout() => "StartTimeout",
+      Error::StopTimeout() => "StopTimeout",
+      Error::VersionNotFound(..) => "VersionNotFound",
+      Error::ChecksumMismatch(..) => "ChecksumMismatch",
+      Error::DownloadFailed(..) => "DownloadFailed"
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match *self {
+      Error::IoError(ref e) => Some(e),
+      _ => None,
     }
   }
 }
@@ -143,13 +279,34 @@ impl fmt::Display for Error {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match *self {
       Error::IoError(ref a) => write!(f, "Io error: {}", a),
-      Error::ServerFilesMissing() => write!(f, "Server files not found"),
-      Error::ServerOffline() => write!(f, "Server offline while called."),
-      Error::ServerAlreadyExists() => write!(f, "Server files already exists"),
+      Error::ServerFilesMissing(ref a) => write!(f, "Server files not found: {} is missing", a.display()),
+      Error::ServerOffline() => write!(f, "Server is offline"),
+      Error::ServerAlreadyExists() => write!(f, "Server files already exist"),
       Error::ThreadError(ref a) => write!(f, "Error while creating {} thread for server", a),
-      Error::ServerProcessExited() => write!(f,"Server processes needed, but has unexpectedly exited."),
-      Error::ServerAlreadyOnline() => write!(f, "Attempted to start already online server"),
-      Error::ServerStillStarting() => write!(f, "Attempted to stop a server that's mid-loading")
+      Error::ServerProcessExited() => write!(f, "Server process has unexpectedly exited"),
+      Error::ServerAlreadyOnline() => write!(f, "Server is already online"),
+      Error::ServerStillStarting() => write!(f, "Server is still starting"),
+      Error::EulaNotAccepted() => write!(f, "The Minecraft EULA has not been accepted, set eula=true in the server's eula.txt"),
+      Error::CommandFailed(ref a) => write!(f, "Command failed: {}", a),
+      Error::InvalidProperty(ref a) => write!(f, "Invalid value for {} in server.properties", a),
+      Error::StdinClosed() => write!(f, "The server's stdin has closed, commands can no longer be sent"),
+      Error::Unsupported(ref a) => write!(f, "Unsupported operation: {}", a),
+      Error::InvalidArgument(ref a) => write!(f, "Invalid argument: {}", a),
+      Error::InvalidCommand(ref a) => write!(f, "Commands can't contain line breaks: {:?}", a),
+      Error::PlayerNotOnline(ref a) => write!(f, "Player {} is not online", a),
+      Error::UnknownItem(ref a) => write!(f, "Unknown item {}", a),
+      Error::RconDisabled() => write!(f, "RCON is disabled, set enable-rcon=true in server.properties"),
+      Error::RconRefused() => write!(f, "RCON connection refused, check rcon.port and that the server is running"),
+      Error::RconAuthFailed() => write!(f, "RCON authentication failed, check rcon.password"),
+      Error::RconTimeout() => write!(f, "Timed out waiting for RCON"),
+      Error::OutOfMemory() => write!(f, "Server exited after running out of memory"),
+      Error::PortInUse(ref a) => write!(f, "Port {} is already in use", a),
+      Error::NoPortsAvailable() => write!(f, "No free ports left in the requested range"),
+      Error::StartTimeout() => write!(f, "Server did not finish starting in time"),
+      Error::StopTimeout() => write!(f, "Server did not stop in time and was killed"),
+      Error::VersionNotFound(ref a) => write!(f, "No server jar for version {} in the version manifest", a),
+      Error::ChecksumMismatch(ref a) => write!(f, "Downloaded server jar for {} does not match its checksum", a),
+      Error::DownloadFailed(ref a) => write!(f, "Download failed: {}", a)
     }
   }
 }
@@ -160,9 +317,364 @@ impl From<std::io::Error> for Error {
   }
 }
 
+/// The largest count accepted by [give](struct.Instance.html#method.give), a hundred stacks of 64
+const MAX_GIVE_COUNT: u32 = 6400;
+
+/// How long to wait for the server to respond to a command before giving up
+const COMMAND_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for an RCON connection or reply
+const RCON_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for the server to answer a server list ping
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long the first call to [resource_usage](struct.Instance.html#method.resource_usage) measures CPU use over
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long a dropped, still running server gets to shut down before it is killed
+const DROP_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait for the stdout and stderr readers to see the end of their pipes after the
+/// server process has exited
+const READER_JOIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Files that [change_version](struct.Manager.html#method.change_version) keeps from the server
+/// rather than taking from the new version, as they hold the server's own settings and player lists
+const PRESERVED_FILES: [&str; 7] = [
+  "server.properties",
+  "eula.txt",
+  "ops.json",
+  "whitelist.json",
+  "banned-players.json",
+  "banned-ips.json",
+  "usercache.json",
+];
+
+/// Copies a file, or a folder and everything in it, merging into folders that already exist
+fn copy_path(from: &Path, to: &Path) -> io::Result<()> {
+  if from.is_dir() {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+      let entry = entry?;
+      copy_path(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+  } else {
+    fs::copy(from, to).map(|_| ())
+  }
+}
+
+/// Checks whether the eula.txt in the given server folder sets `eula` to true, reading it as a
+/// properties file the way the server does
+fn eula_accepted(server_folder: &Path) -> bool {
+  match PropertiesFile::load(&server_folder.join("eula.txt")) {
+    Ok(eula) => eula.get("eula").is_some_and(|value| value.trim().eq_ignore_ascii_case("true")),
+    Err(_) => false,
+  }
+}
+
+/// Reads the RCON port and password from server.properties, or None if RCON is disabled
+fn rcon_settings(props: &PropertiesFile) -> Result<Option<(u16, String)>> {
+  if !property(props, "enable-rcon", false)? {
+    return Ok(None);
+  }
+  let port = property(props, "rcon.port", 25575)?;
+  let password = props.get("rcon.password").unwrap_or("").to_string();
+  Ok(Some((port, password)))
+}
+
+/// The address the server and RCON listen on: server-ip if it is set, otherwise the local machine
+fn server_address(props: &PropertiesFile) -> String {
+  match props.get("server-ip").map(|ip| ip.trim()) {
+    None | Some("") => String::from("127.0.0.1"),
+    Some(ip) => ip.to_string(),
+  }
+}
+
+/// Waits for a stdout or stderr reader to finish, leaving it behind if it doesn't in time.
+/// Their pipes only end once every process holding them has exited, so a child the server spawned
+/// could otherwise keep the reader, and whoever is joining it, blocked indefinitely
+fn join_reader(handle: thread::JoinHandle<()>, deadline: Instant) {
+  while !handle.is_finished() && Instant::now() < deadline {
+    thread::sleep(Duration::from_millis(10));
+  }
+  if handle.is_finished() {
+    let _ = handle.join();
+  }
+}
+
+/// Waits for the server process to exit once its output has ended, recording its status in `exit`.
+/// Returns false if the threads were told to finish first
+///
+/// The status is read with WNOWAIT, leaving the process for the Instance to reap. Reaping happens
+/// with `exit` locked and sets exited, so while it is unset the pid can't belong to anything else
+#[cfg(target_os = "linux")]
+fn watch_exit(pid: u32, exit: &Mutex<ProcessExit>, alive: &RwLock<bool>) -> bool {
+  loop {
+    {
+      let mut exit = exit.lock().unwrap();
+      if exit.exited {
+        return true;
+      }
+      match peek_exit_status(pid) {
+        Ok(Some(status)) => {
+          exit.record(status);
+          return true;
+        }
+        Ok(None) => {}
+        Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+        //Reaped through the public server_process field, so it has certainly exited
+        Err(_) => {
+          exit.exited = true;
+          return true;
+        }
+      }
+    }
+    if !*alive.read().unwrap() {
+      return false;
+    }
+    thread::sleep(Duration::from_millis(50));
+  }
+}
+#[cfg(not(target_os = "linux"))]
+fn watch_exit(_pid: u32, exit: &Mutex<ProcessExit>, _alive: &RwLock<bool>) -> bool {
+  exit.lock().unwrap().exited = true;
+  true
+}
+
+/// Reads the exit status of a child that has exited, without reaping it
+#[cfg(target_os = "linux")]
+fn peek_exit_status(pid: u32) -> io::Result<Option<ExitStatus>> {
+  use std::os::unix::process::ExitStatusExt;
+  //Safe as siginfo_t is plain data, which waitid fills in
+  let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+  let flags = libc::WEXITED | libc::WNOHANG | libc::WNOWAIT;
+  if unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, flags) } != 0 {
+    return Err(io::Error::last_os_error());
+  }
+  //With WNOHANG a still running child leaves the pid at 0
+  if unsafe { info.si_pid() } == 0 {
+    return Ok(None);
+  }
+  let status = unsafe { info.si_status() };
+  //Rebuild the wait status that ExitStatus wraps
+  let raw = match info.si_code {
+    libc::CLD_EXITED => (status & 0xff) << 8,
+    libc::CLD_DUMPED => status | 0x80,
+    _ => status,
+  };
+  Ok(Some(ExitStatus::from_raw(raw)))
+}
+
+/// Checks whether a line was printed by the JVM's garbage collector logging
+fn is_gc_log_line(line: &str) -> bool {
+  //Unified logging, e.g. [0.015s][info][gc,heap] Heap region size: 1M
+  let unified = line.starts_with('[') && (line.contains("][gc]") || line.contains("][gc,"));
+  //Legacy logging, e.g. 1.204: [GC (Allocation Failure) ...] or [Full GC (Ergonomics) ...]
+  let legacy = line.contains("[GC (") || line.contains("[GC pause") || line.contains("[Full GC");
+  unified || legacy
+}
+
+/// Checks that a name is a valid Minecraft username, so it can be safely placed in a command
+fn validate_player_name(name: &str) -> Result<()> {
+  let valid = !name.is_empty()
+    && name.len() <= 16
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::InvalidArgument(format!("{:?} is not a valid player name", name)))
+  }
+}
+
+/// Checks that an item id is a resource location such as `diamond` or `minecraft:diamond`
+fn validate_item_id(item: &str) -> Result<()> {
+  let valid = !item.is_empty()
+    && item.splitn(2, ':').all(|part| {
+      !part.is_empty()
+        && part
+          .chars()
+          .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "_-./".contains(c))
+    });
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::InvalidArgument(format!("{:?} is not a valid item id", item)))
+  }
+}
+
+/// Checks that a `give` count is one the server accepts
+fn validate_give_count(count: u32) -> Result<()> {
+  if count == 0 || count > MAX_GIVE_COUNT {
+    return Err(Error::InvalidArgument(format!(
+      "item count must be between 1 and {}, got {}",
+      MAX_GIVE_COUNT, count
+    )));
+  }
+  Ok(())
+}
+
+/// Checks that a server id names a single folder inside the server files folder, as it is joined
+/// onto that folder and an id such as `..` would point operations like delete somewhere else
+fn validate_server_id(id: &str) -> Result<()> {
+  let valid = !id.is_empty()
+    && id != "."
+    && id != ".."
+    && !id.chars().any(|c| c == '/' || c == '\\' || c.is_control());
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::InvalidArgument(format!("{:?} is not a valid server id", id)))
+  }
+}
+
+/// Checks that a string is a UUID in its hyphenated form, as used in the server's player lists
+fn validate_uuid(uuid: &str) -> Result<()> {
+  let valid = uuid.len() == 36
+    && uuid.char_indices().all(|(i, c)| match i {
+      8 | 13 | 18 | 23 => c == '-',
+      _ => c.is_ascii_hexdigit(),
+    });
+  if valid {
+    Ok(())
+  } else {
+    Err(Error::InvalidArgument(format!("{:?} is not a valid UUID", uuid)))
+  }
+}
+
+/// Checks that a kick or ban reason fits on one line, as a line break would end the command and
+/// send the rest to the console as a command of its own
+fn validate_reason(reason: &str) -> Result<()> {
+  if reason.contains(['\n', '\r']) {
+    return Err(Error::InvalidArgument(String::from("reasons can't contain line breaks")));
+  }
+  Ok(())
+}
+
+/// Checks a message for the server's stdin holds a single console command
+fn validate_command(msg: &str) -> Result<()> {
+  if msg.contains(['\n', '\r']) {
+    return Err(Error::InvalidCommand(msg.to_string()));
+  }
+  Ok(())
+}
+
+/// Builds a moderation command, appending the reason if there is one
+fn with_reason(command: String, reason: Option<&str>) -> Result<String> {
+  match reason {
+    Some(reason) => {
+      validate_reason(reason)?;
+      Ok(format!("{} {}", command, reason))
+    }
+    None => Ok(command),
+  }
+}
+
+/// Parses a `<name> joined the game` or `<name> left the game` console line into the
+/// player's name and whether they joined
+fn player_event(line: &str) -> Option<(String, bool)> {
+  let index = line.find("]: ")?;
+  let message = &line[index + 3..];
+  let (name, joined) = if let Some(name) = message.strip_suffix(" joined the game") {
+    (name, true)
+  } else if let Some(name) = message.strip_suffix(" left the game") {
+    (name, false)
+  } else {
+    return None;
+  };
+  //Also rules out chat messages, which start with <name>
+  validate_player_name(name).ok()?;
+  Some((name.to_string(), joined))
+}
+
+/// Parses the comma separated names after `marker` in a reply, such as `players online:` in
+/// `There are 2 of a max of 20 players online: a, b`, returning none if the marker is missing
+fn parse_name_list(reply: &str, marker: &str) -> Vec<String> {
+  match reply.find(marker) {
+    Some(index) => reply[index + marker.len()..]
+      .split(',')
+      .map(|name| name.trim())
+      .filter(|name| !name.is_empty())
+      .map(String::from)
+      .collect(),
+    None => Vec::new(),
+  }
+}
+
+/// Reads N from the `There are N ban(s):` header of a banlist reply, also written `N bans:`
+fn parse_ban_count(line: &str) -> Option<usize> {
+  let rest = &line[line.rfind("There are ")? + "There are ".len()..];
+  let count = rest.strip_suffix(" ban(s):").or_else(|| rest.strip_suffix(" bans:"))?;
+  count.parse().ok()
+}
+
+/// Reads the banned name or IP address from a `<target> was banned by <source>: <reason>` line
+fn parse_ban_entry(line: &str) -> Option<String> {
+  let message = line.find("]: ").map_or(line, |index| &line[index + 3..]);
+  message.find(" was banned by ").map(|index| message[..index].to_string())
+}
+
+/// Formats a coordinate for a command, optionally as a `~` relative offset
+fn coordinate(value: f64, relative: bool) -> Result<String> {
+  if !value.is_finite() {
+    return Err(Error::InvalidArgument(format!("{} is not a valid coordinate", value)));
+  }
+  if relative {
+    if value == 0.0 {
+      return Ok(String::from("~"));
+    }
+    return Ok(format!("~{}", value));
+  }
+  //Whole numbers need a decimal point, otherwise the server centers them on the block
+  if value.fract() == 0.0 {
+    Ok(format!("{:.1}", value))
+  } else {
+    Ok(format!("{}", value))
+  }
+}
+
+/// Why a server process gave up, as recognized from its console output
+#[derive(Debug, Clone, Copy)]
+enum Failure {
+  Eula,
+  OutOfMemory,
+  PortBind,
+}
+
+/// What is known about the server process having exited, shared with the stdout thread
+#[derive(Debug, Default)]
+struct ProcessExit {
+  /// Whether the process has exited. Where the status can't be read without reaping the process,
+  /// this is set once its output ends
+  exited: bool,
+  status: Option<ExitStatus>,
+  /// Whether it exited on its own after it finished starting
+  crashed: bool,
+}
+
+impl ProcessExit {
+  fn record(&mut self, status: ExitStatus) {
+    self.exited = true;
+    self.status = Some(status);
+  }
+}
+
 /// Controls the creation and deleting of servers, and whether they are currently active.
 pub struct Manager {
-  server: Option<Instance>,
+  server_files_folder: PathBuf,
+  version_folder: PathBuf,
+  servers: HashMap<String, Instance>,
+  ready_grace: Duration,
+  separate_gc_log: bool,
+  ready_fallback: Option<Duration>,
+  console_patterns: ConsolePatterns,
+  reserved_ports: HashSet<u32>,
+  world_container: Option<PathBuf>,
+  stop_timeout: Duration,
+  exit_statuses: HashMap<String, ExitStatus>,
+  path_resolver: Option<PathResolver>,
+  all_subscribers: TaggedSubscribers,
 }
 
 impl Manager {
@@ -172,37 +684,135 @@ impl Manager {
   /// * `version_folder` - the folder containing the base files of servers for the MC versions that you wish to host. Used as a base to create new servers.
   /// # Examples
   /// ```
-  ///   let manager = serbo::Manager::new();
+  ///   let manager = serbo::Manager::new("servers", "versions");
   /// ```
   /// # Remarks
   /// The version_folder should be a folder that contains folders that are named the same as the MC server files they contain.
-  pub fn new() -> Manager {
+  pub fn new(server_files_folder: &str, version_folder: &str) -> Manager {
     Manager {
-      server: None,
+      server_files_folder: PathBuf::from(server_files_folder),
+      version_folder: PathBuf::from(version_folder),
+      servers: HashMap::new(),
+      ready_grace: Duration::from_secs(0),
+      separate_gc_log: false,
+      ready_fallback: None,
+      console_patterns: ConsolePatterns::default(),
+      reserved_ports: HashSet::new(),
+      world_container: None,
+      stop_timeout: Duration::from_secs(60),
+      exit_statuses: HashMap::new(),
+      path_resolver: None,
+      all_subscribers: Arc::new(Mutex::new(Vec::new())),
     }
   }
+  /// Sets how long to wait after the server reports it is ready before treating it as ready
+  /// # Arguments
+  /// * `grace` - The delay applied after the readiness line is seen. Defaults to no delay
+  ///
+  /// # Remarks
+  /// Some servers print their readiness line slightly before they accept commands. A short grace
+  /// period keeps [stop](#method.stop) and other commands from racing the server's startup.
+  /// Applies to servers started after the call.
+  pub fn set_ready_grace(&mut self, grace: Duration) {
+    self.ready_grace = grace;
+  }
+  /// Sets how long a server may go without printing its readiness line before it is assumed ready
+  /// # Arguments
+  /// * `fallback` - How long after launch to clear the starting state, or None to wait for the readiness line indefinitely (the default)
+  ///
+  /// # Remarks
+  /// Meant for server software that never prints a recognizable readiness line, which would
+  /// otherwise stay starting forever and refuse to [stop](#method.stop). See also
+  /// [Instance::mark_ready](struct.Instance.html#method.mark_ready). Applies to servers started after the call.
+  pub fn set_ready_fallback(&mut self, fallback: Option<Duration>) {
+    self.ready_fallback = fallback;
+  }
+  /// Sets the patterns used to recognize readiness, shutdown and failures in the console output
+  /// # Arguments
+  /// * `patterns` - The patterns to use, see [ConsolePatterns](struct.ConsolePatterns.html) for the defaults
+  ///
+  /// # Remarks
+  /// Applies to servers started after the call.
+  pub fn set_console_patterns(&mut self, patterns: ConsolePatterns) {
+    self.console_patterns = patterns;
+  }
+  /// Sets a folder to hold world data outside of the server folder, e.g. on a faster disk
+  /// # Arguments
+  /// * `folder` - The folder worlds are loaded from and saved to, or None to keep them in the server folder (the default)
+  ///
+  /// # Remarks
+  /// Each server gets its own folder inside it, `{folder}/{id}`, which is created on start and
+  /// passed to the server as `--universe`; vanilla, Fabric, Forge and Paper all accept it. The world
+  /// inside that is still picked by level-name, and [backup](#method.backup) and
+  /// [restore](#method.restore) use the same folder. Only the world data can be relocated this
+  /// way: the server always writes its logs/ folder next to the jar, so moving logs needs a
+  /// symlink or a custom log4j configuration. Applies to servers started after the call.
+  pub fn set_world_container(&mut self, folder: Option<PathBuf>) {
+    self.world_container = folder;
+  }
+  /// Sets where each server's folder is, in place of `{server_files_folder}/{id}`
+  /// # Arguments
+  /// * `resolver` - Maps a server id to the folder holding its files
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use std::path::PathBuf;
+  ///
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// //Keep the busiest server on its own disk
+  /// manager.set_path_resolver(|id| match id {
+  ///   "survival" => PathBuf::from("/mnt/fast/survival"),
+  ///   id => PathBuf::from("servers").join(id),
+  /// });
+  /// ```
+  /// # Remarks
+  /// Every operation that touches a server's files goes through the resolver, including starting,
+  /// creating, deleting, properties, player lists and backups. [list_all](#method.list_all) still
+  /// only lists the folders in the server files folder, as a resolver can't be enumerated. Set it
+  /// before starting any servers: running servers keep the folder they were started in, while the
+  /// Manager's own file operations switch to the new folders straight away.
+  pub fn set_path_resolver<F>(&mut self, resolver: F)
+  where
+    F: Fn(&str) -> PathBuf + Send + Sync + 'static,
+  {
+    self.path_resolver = Some(Box::new(resolver));
+  }
+  /// Sets how long [stop](#method.stop) waits for a server to shut down before killing it
+  /// # Arguments
+  /// * `timeout` - How long the server gets to save and exit after `/stop`. Defaults to 60 seconds
+  ///
+  /// # Remarks
+  /// Large worlds can take a while to save, so keep this generous; a killed server loses any
+  /// chunks it hadn't written yet. Servers started with their own
+  /// [stop_timeout](struct.StartOptions.html#structfield.stop_timeout) use that instead.
+  pub fn set_stop_timeout(&mut self, timeout: Duration) {
+    self.stop_timeout = timeout;
+  }
+  /// Sets whether JVM garbage collector log lines are kept apart from the console log
+  /// # Arguments
+  /// * `separate` - When true, GC lines go to [gc_log](struct.Instance.html#method.gc_log) instead of the console log
+  ///
+  /// # Remarks
+  /// Only matters when GC logging is enabled for the JVM. Both the unified `-Xlog:gc` format and
+  /// the legacy `-XX:+PrintGC` format are recognized. Applies to servers started after the call.
+  pub fn set_separate_gc_log(&mut self, separate: bool) {
+    self.separate_gc_log = separate;
+  }
   /// Returns an Option<t> containing a [Instance](struct.Instance.html) that represents the currently online server represented by the provided id
   /// # Arguments
   /// * `id` - The id that represents the requested server
   /// # Examples
-  /// ```
-  /// let mut manager = serbo::Manager::new();
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
   /// //Returns an Option
-  /// let instance = manager.get().unwrap();
+  /// let instance = manager.get("1").unwrap();
   /// ```
   /// # Remarks
   /// Queries the currently online servers, for get to return, must have been launched by calling [start](struct.Manager.html#method.start)
-  pub fn get(&mut self) -> Option<&mut Instance> {
-    if let Some(ref mut server) = self.server {
-      if let Ok(bol) = server.is_valid() {
-        if bol {
-          Some(server)
-        } else {
-          None
-        }
-      } else {
-        None
-      }
+  pub fn get(&mut self, id: &str) -> Option<&mut Instance> {
+    let server = self.servers.get_mut(id)?;
+    if let Ok(true) = server.is_valid() {
+      Some(server)
     } else {
       None
     }
@@ -210,211 +820,3321 @@ impl Manager {
   /// Checks if server files exist for a given id
   /// # Arguments
   /// * `id` - The id that represents the requested server
-  pub fn exists(&mut self) -> bool {
-    Path::new(&format!("./server")).exists()
+  pub fn exists(&self, id: &str) -> bool {
+    match self.server_folder(id) {
+      Ok(folder) => folder.exists(),
+      Err(_) => false,
+    }
   }
-  /// Checks if the server is online
-  /// # Arguments
-  /// * `id` - The id that represents the requested server
+  /// Lists the ids of the servers that are currently online, sorted by id
+  ///
   /// # Remarks
-  /// Queries the currently online servers, must have been launched by calling [start](struct.Manager.html#method.start)
-  pub fn is_online(&mut self) -> bool {
-    match self.get() {
-      Some(_) => true,
-      None => false,
-    }
+  /// Servers whose process has exited since they were started aren't included, the same as for
+  /// [is_online](#method.is_online). The exits are the ones each server's stdout thread has
+  /// already seen, so this only needs to read shared state.
+  pub fn list_online(&self) -> Vec<String> {
+    let mut ids: Vec<String> = self
+      .servers
+      .iter()
+      .filter(|(_, inst)| !inst.has_exited())
+      .map(|(id, _)| id.clone())
+      .collect();
+    ids.sort();
+    ids
   }
-  /// Launches a server
-  /// # Arguments
-  /// * `id` - The id that represents the requested server
-  /// * `port` - The port that the server should be started on
-  pub fn start(&mut self, port: u32) -> Result<u32> {
-    if let Some(_) = self.server {
-      Err(Error::ServerAlreadyOnline())
-    } else {
-      let mut command = Command::new("java");
-      command
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .args(&[
-          "-Xmx4G",
-          "-Xms1G",
-          "-jar",
-          "server.jar",
-          "nogui",
-          "--port",
-          &port.to_string(),
-        ])
-        .current_dir(format!("./server"));
-      let child = command.spawn()?;
-      let mut serv_inst = Instance {
-        server_process: child,
-        stdout_join: None,
-        stdin_join: None,
-        console_log: Arc::new(Mutex::new(Vec::new())),
-        stdin_queue: Arc::new(Mutex::new(Vec::new())),
-        thread_cond: Arc::new(RwLock::new(true)),
-        starting: Arc::new(RwLock::new(true)),
-        started: false,
-        port
-      };
-      let stdout = match serv_inst.server_process.stdout.take() {
-        Some(e) => e,
-        None => return Err(Error::ThreadError("stdout".to_string())),
-      };
-      let stdin = match serv_inst.server_process.stdin.take() {
-        Some(e) => e,
-        None => return Err(Error::ThreadError("stdin".to_string())),
-      };
-
-      let starting_lock = serv_inst.starting.clone();
-      let stdout_arc = serv_inst.console_log.clone();
-      let stdin_arc = serv_inst.stdin_queue.clone();
-      let cond_reader1 = serv_inst.thread_cond.clone();
-      let cond_reader2 = serv_inst.thread_cond.clone();
-
-      let stdout_thread_handle = thread::spawn(move || {
-        let mut reader = BufReader::new(stdout).lines();
-        loop {
-          let r1 = cond_reader1.read().unwrap();
-          if !*r1{
-            break;
-          }
-          drop(r1);
-          if let Some(line) = reader.next() {
-            match line {
-              Ok(a) => {
-                if a.contains("[Server] SERVER READY") || a.contains("[CONSOLE] SERVER READY"){
-                  println!("READY");
-                  let mut g = starting_lock.write().unwrap();
-                  *g = false;
-                }
-
-                let mut lock = stdout_arc.lock().unwrap();
-                lock.push(a);
-              },
-              _ => {}
-            };
-          }
-        }
-      });
-
-      let stdin_thread_handle = thread::spawn(move || {
-        let mut writer = BufWriter::new(stdin);
-        loop {
-          let mut vec = stdin_arc.lock().unwrap();
-          let r1 = cond_reader2.read().unwrap();
-          if !*r1 && vec.len() == 0{
-            break;
-          }
-          drop(r1);
-          vec.drain(..).for_each(|x| {
-            writeln!(writer, "{}", x);
-            writer.flush();
-          });
-          drop(vec);
-        }
-      });
-      serv_inst.send("say SERVER READY".to_string())?;
-      serv_inst.send("/say SERVER READY".to_string())?;
-      serv_inst.stdout_join = Some(stdout_thread_handle);
-      serv_inst.stdin_join = Some(stdin_thread_handle);
-      self.server.insert(serv_inst);
-      Ok(port)
+  /// Lists the servers that are currently online along with the port each was started on, sorted by id
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// manager.start("2", 0).unwrap();
+  /// for (id, port) in manager.running() {
+  ///   println!("{} is listening on {}", id, port);
+  /// }
+  /// ```
+  /// # Remarks
+  /// Includes the same servers as [list_online](#method.list_online). Servers started with port 0
+  /// report the port chosen for them. Use [list_all](#method.list_all) for offline servers too.
+  pub fn running(&self) -> Vec<(String, u32)> {
+    let mut servers: Vec<(String, u32)> = self
+      .servers
+      .iter()
+      .filter(|(_, inst)| !inst.has_exited())
+      .map(|(id, inst)| (id.clone(), inst.port))
+      .collect();
+    servers.sort();
+    servers
+  }
+  /// Lists the ids of every server, online or not, the names of the folders in the server files folder
+  ///
+  /// # Remarks
+  /// Sorted by id. Read from disk on each call, like [available_versions](#method.available_versions).
+  /// Servers placed elsewhere by a [path resolver](#method.set_path_resolver) aren't found.
+  pub fn list_all(&self) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(&self.server_files_folder)? {
+      let entry = entry?;
+      if entry.file_type()?.is_dir() {
+        ids.push(entry.file_name().to_string_lossy().into_owned());
+      }
     }
+    ids.sort();
+    Ok(ids)
   }
-  /// Stops a server
-  /// # Arguments
-  /// * `id` - The id that represents the requested server
-  pub fn stop(&mut self) -> Result<()> {
-    if let Some(ref mut inst) = self.server {
-      let is_starting = *inst.starting.read().unwrap();
-      if !is_starting || inst.started {
-        inst.stop()?;
-        let rw = inst.thread_cond.clone();
-        let mut d = rw.write().unwrap();
-        *d = false;
-        drop(d);
-        drop(rw);
-        inst.stdout_join.take().unwrap().join();
-        inst.stdin_join.take().unwrap().join();
-        inst.server_process.wait();
-        self.server.take();
-        return Ok(());
+  /// Iterates over the started servers along with their ids, in no particular order
+  ///
+  /// # Remarks
+  /// Unlike [list_online](#method.list_online) this doesn't filter out exited processes, so it also
+  /// yields servers that have exited but haven't been cleaned up by [stop](#method.stop) or
+  /// [recover_crashed](#method.recover_crashed) yet.
+  pub fn iter_instances(&self) -> impl Iterator<Item = (&str, &Instance)> {
+    self.servers.iter().map(|(id, inst)| (id.as_str(), inst))
+  }
+  /// Creates a channel that receives every stdout line printed by any server from now on, as
+  /// `(id, line)`
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let lines = manager.subscribe_all();
+  /// manager.start("1", 25565).unwrap();
+  /// manager.start("2", 25566).unwrap();
+  /// for (id, line) in lines.iter() {
+  ///   println!("[{}] {}", id, line);
+  /// }
+  /// ```
+  /// # Remarks
+  /// Covers the servers already online and every server started later, including restarts. A
+  /// server that stops simply sends nothing more, the channel stays open for the others until the
+  /// Manager is dropped and every server's output has ended. Lines already in the console logs are
+  /// not replayed. Dropping the receiver unregisters it. See
+  /// [Instance::subscribe](struct.Instance.html#method.subscribe) to follow a single server.
+  pub fn subscribe_all(&self) -> Receiver<(String, String)> {
+    let (tx, rx) = mpsc::channel();
+    self.all_subscribers.lock().unwrap().push(tx);
+    rx
+  }
+  /// Lists the versions servers can be created from, the names of the folders in the version folder
+  ///
+  /// # Remarks
+  /// Sorted by name. Read from disk on each call, so versions added or removed while running are
+  /// picked up.
+  pub fn available_versions(&self) -> Result<Vec<String>> {
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(&self.version_folder)? {
+      let entry = entry?;
+      if entry.file_type()?.is_dir() {
+        versions.push(entry.file_name().to_string_lossy().into_owned());
       }
-      return Err(Error::ServerStillStarting());
     }
-    Err(Error::ServerOffline())
+    versions.sort();
+    Ok(versions)
   }
-}
-
-/// Represents a currently online server.
-/// Created by calling [start](struct.Manager.html#method.start) from a [Manager](struct.Manager.html)
-#[derive(Debug)]
-pub struct Instance {
-  pub server_process: Child,
-  stdout_join: Option<thread::JoinHandle<()>>,
-  stdin_join: Option<thread::JoinHandle<()>>,
-  console_log: Arc<Mutex<Vec<String>>>,
-  stdin_queue: Arc<Mutex<Vec<String>>>,
-  thread_cond: Arc<RwLock<bool>>,
-  starting: Arc<RwLock<bool>>,
-  started: bool,
-  pub port: u32,
-}
-
-impl Instance {
-  /// Stops the server, killing the server process and the stdin
-  /// and stdout threads
-  pub fn stop(&mut self) -> Result<()> {
-    let _ = self.process_check();
-    self.send(String::from("/stop"))?;
+  /// The folder holding a version's base files, if it is one of the available versions
+  fn version_template(&self, version: &str) -> Result<PathBuf> {
+    match self.available_versions() {
+      Ok(ref versions) if versions.iter().any(|v| v == version) => Ok(self.version_folder.join(version)),
+      _ => Err(Error::ServerFilesMissing(self.version_folder.join(version))),
+    }
+  }
+  /// Downloads the vanilla server jar for a Minecraft version into the version folder, so servers
+  /// can be [created](#method.create) from it
+  /// # Arguments
+  /// * `version` - The version id as listed in Mojang's version manifest, e.g. `1.16.1`
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.fetch_version("1.16.1").unwrap();
+  /// manager.create("1", &serbo::CreateOptions::new("1.16.1")).unwrap();
+  /// ```
+  /// # Remarks
+  /// Only available with the `download` feature. The jar is written to
+  /// `{version_folder}/{version}/server.jar`, replacing any jar already there, once its SHA-1 has
+  /// been checked against the manifest. Returns [VersionNotFound](enum.Error.html#variant.VersionNotFound)
+  /// if the manifest has no server jar for the version and
+  /// [ChecksumMismatch](enum.Error.html#variant.ChecksumMismatch) if the download is corrupt.
+  #[cfg(feature = "download")]
+  pub fn fetch_version(&mut self, version: &str) -> Result<()> {
+    let jar = download::server_jar(version)?;
+    let folder = self.version_folder.join(version);
+    fs::create_dir_all(&folder)?;
+    fs::write(folder.join("server.jar"), jar)?;
     Ok(())
   }
-  /// Checks if the server process is still valid (has not crashed or exited).
-  pub fn is_valid(&mut self) -> Result<bool> {
-    match self.server_process.try_wait()? {
-      Some(_) => Ok(false),
-      None => Ok(true),
+  /// Creates a new server from a version's base files
+  /// # Arguments
+  /// * `id` - The id that will represent the new server
+  /// * `options` - The version to create the server from and the settings to give it
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let mut options = serbo::CreateOptions::new("1.16.1");
+  /// options.allow_flight = Some(true);
+  /// manager.create("1", &options).unwrap();
+  /// manager.start("1", 25565).unwrap();
+  /// ```
+  /// # Remarks
+  /// Copies everything in the version's folder into a new folder for the server, then writes the
+  /// options that are set to its server.properties. An imported world is copied last, into the
+  /// server's level-name folder in its [world folder](#method.world_folder), replacing any world
+  /// the version came with. Returns
+  /// [ServerAlreadyExists](enum.Error.html#variant.ServerAlreadyExists) if the server's folder is
+  /// already there, and [ServerFilesMissing](enum.Error.html#variant.ServerFilesMissing) if the
+  /// version isn't one of the [available versions](#method.available_versions) or the imported
+  /// world has no level.dat, in which case nothing is created.
+  pub fn create(&mut self, id: &str, options: &CreateOptions) -> Result<()> {
+    let template = self.version_template(&options.version)?;
+    let folder = self.server_folder(id)?;
+    if folder.exists() {
+      return Err(Error::ServerAlreadyExists());
     }
-  }
-  fn process_check(&mut self) -> Result<()> {
-    match self.is_valid()? {
-      true => Ok(()),
-      false => Err(Error::ServerProcessExited()),
+    if let Some(ref world) = options.world_import {
+      let level_dat = world.join("level.dat");
+      if !level_dat.is_file() {
+        return Err(Error::ServerFilesMissing(level_dat));
+      }
+    }
+    copy_path(&template, &folder)?;
+    let settings = [
+      ("allow-flight", options.allow_flight),
+      ("force-gamemode", options.force_gamemode),
+      ("allow-nether", options.allow_nether),
+    ];
+    if settings.iter().any(|(_, value)| value.is_some()) {
+      let mut props = self.load_properties(id)?;
+      for (key, value) in settings.iter() {
+        if let Some(value) = value {
+          props.set(key, &value.to_string());
+        }
+      }
+      self.save_properties(id, &props)?;
+    }
+    if let Some(ref world) = options.world_import {
+      let level = self.world_folder(id)?.join(self.level_name(id)?);
+      if level.exists() {
+        fs::remove_dir_all(&level)?;
+      }
+      copy_path(world, &level)?;
     }
+    Ok(())
   }
-  /// Sends a string to the server stdin
+  /// Deletes a server's folder, including its worlds
   /// # Arguments
-  /// * `msg` - A String that contains the message to be sent to the server.
+  /// * `id` - The id that represents the requested server
   ///
   /// # Remarks
-  /// The message should not contain a trailing newline, as the send method handles it.
-  pub fn send(&mut self, msg: String) -> Result<()> {
-    let _ = self.process_check()?;
-    let vec_lock = self.stdin_queue.clone();
-    let mut vec = vec_lock.lock().unwrap();
-    vec.push(msg);
+  /// Returns [ServerAlreadyOnline](enum.Error.html#variant.ServerAlreadyOnline) if the server is
+  /// running, it has to be stopped first. Ids that aren't a plain folder name, such as `..` or ones
+  /// containing a path separator, are refused with
+  /// [InvalidArgument](enum.Error.html#variant.InvalidArgument), as with every other operation.
+  pub fn delete(&mut self, id: &str) -> Result<()> {
+    if self.is_online(id) {
+      return Err(Error::ServerAlreadyOnline());
+    }
+    let folder = self.server_folder(id)?;
+    if !folder.exists() {
+      return Err(Error::ServerFilesMissing(folder));
+    }
+    fs::remove_dir_all(folder)?;
     Ok(())
   }
-  //// Gets the output from server stdout
-  ///  # Arguments
-  ///  * `start` The line number of the first line that should be returned
+  /// Switches a server to another version's base files, keeping its worlds and settings
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `version` - The name of a folder in the version folder, e.g. `1.16.1`
   ///
   /// # Remarks
-  /// The returned Vec will contain the lines in the range of start to the end of output
-  pub fn get(&self, start: u32) -> Vec<String> {
-    let vec_lock = self.console_log.clone();
-    let vec = vec_lock.lock().unwrap();
-    let mut start_line = start as usize;
-    if start_line > vec.len() {
-      start_line = vec.len()
+  /// The jars in the server's folder are removed, then the version's files are copied over the
+  /// rest. The world folders named by level-name, server.properties, eula.txt and the player lists
+  /// are kept as they are. Returns [ServerAlreadyOnline](enum.Error.html#variant.ServerAlreadyOnline)
+  /// if the server is running, and [ServerFilesMissing](enum.Error.html#variant.ServerFilesMissing)
+  /// if the server doesn't exist or the version isn't one of the
+  /// [available versions](#method.available_versions).
+  pub fn change_version(&mut self, id: &str, version: &str) -> Result<()> {
+    let template = self.version_template(version)?;
+    let folder = self.server_folder(id)?;
+    if !folder.is_dir() {
+      return Err(Error::ServerFilesMissing(folder));
     }
-    Vec::from(&vec[start_line..])
-  }
-
+    if self.is_online(id) {
+      return Err(Error::ServerAlreadyOnline());
+    }
+    let level = self.level_name(id)?;
+    let worlds = [level.clone(), format!("{}_nether", level), format!("{}_the_end", level)];
+    //Drop the old jars so a launcher from the previous version can't be picked up
+    for entry in fs::read_dir(&folder)? {
+      let path = entry?.path();
+      if path.is_file() && path.extension() == Some("jar".as_ref()) {
+        fs::remove_file(path)?;
+      }
+    }
+    for entry in fs::read_dir(&template)? {
+      let entry = entry?;
+      let name = entry.file_name().to_string_lossy().into_owned();
+      let target = folder.join(&name);
+      let preserved = worlds.contains(&name) || PRESERVED_FILES.contains(&name.as_str());
+      if preserved && target.exists() {
+        continue;
+      }
+      copy_path(&entry.path(), &target)?;
+    }
+    Ok(())
+  }
+  /// Zips a server's world folders into a timestamped archive
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `dest` - The folder to write the archive to, created if missing
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use std::path::Path;
+  ///
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let archive = manager.backup("1", Path::new("backups")).unwrap();
+  /// manager.restore("1", &archive).unwrap();
+  /// ```
+  /// # Remarks
+  /// Returns the path of the archive, named `{id}-{unix time}.zip`. The world named by level-name
+  /// is included along with its `_nether` and `_the_end` folders, if present. An online server
+  /// keeps running: saving is turned off with `save-off`, the world is flushed with
+  /// `save-all flush`, and saving is turned back on with `save-on` once the archive is written.
+  /// Returns [ServerAlreadyOnline](enum.Error.html#variant.ServerAlreadyOnline) for a server that
+  /// is still starting, as it can't flush its world yet.
+  pub fn backup(&mut self, id: &str, dest: &Path) -> Result<PathBuf> {
+    let base = self.world_folder(id)?;
+    let level = self.level_name(id)?;
+    let folders: Vec<String> = [level.clone(), format!("{}_nether", level), format!("{}_the_end", level)]
+      .iter()
+      .filter(|folder| base.join(folder).is_dir())
+      .cloned()
+      .collect();
+    if folders.is_empty() {
+      return Err(Error::ServerFilesMissing(base.join(level)));
+    }
+    let online = match self.get(id) {
+      Some(inst) => {
+        if !inst.is_ready() {
+          return Err(Error::ServerAlreadyOnline());
+        }
+        inst.send(String::from("save-off"))?;
+        let flushed = inst.send_and_confirm(String::from("save-all flush"), |line| line.contains("Saved the game"));
+        if let Err(e) = flushed {
+          let _ = inst.send(String::from("save-on"));
+          return Err(e);
+        }
+        true
+      }
+      None => false,
+    };
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|t| t.as_secs()).unwrap_or(0);
+    let archive = dest.join(format!("{}-{}.zip", id, time));
+    let result = fs::create_dir_all(dest).and_then(|_| backup::write_archive(&base, &folders, &archive));
+    if online {
+      if let Some(inst) = self.get(id) {
+        let _ = inst.send(String::from("save-on"));
+      }
+    }
+    result?;
+    Ok(archive)
+  }
+  /// Replaces a server's worlds with the ones in an archive made by [backup](#method.backup)
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `archive` - The zip archive to restore
+  ///
+  /// # Remarks
+  /// Every world folder in the archive replaces the folder of the same name, which is removed
+  /// first. Returns [ServerAlreadyOnline](enum.Error.html#variant.ServerAlreadyOnline) if the server
+  /// is running, it has to be stopped first.
+  ///
+  /// Only the folders [backup](#method.backup) writes are restored: the server's level-name folder
+  /// and its `_nether` and `_the_end` folders. An archive with anything else in it, such as
+  /// ops.json, server.properties or a jar, is refused with an
+  /// [IoError](enum.Error.html#variant.IoError) before anything is changed.
+  pub fn restore(&mut self, id: &str, archive: &Path) -> Result<()> {
+    if self.is_online(id) {
+      return Err(Error::ServerAlreadyOnline());
+    }
+    let folder = self.server_folder(id)?;
+    if !folder.is_dir() {
+      return Err(Error::ServerFilesMissing(folder));
+    }
+    let level = self.level_name(id)?;
+    let worlds = [level.clone(), format!("{}_nether", level), format!("{}_the_end", level)];
+    backup::extract_archive(archive, &self.world_folder(id)?, &worlds)?;
+    Ok(())
+  }
+  /// The folder a server's worlds are kept in, its own folder in the world container if one is set
+  fn world_folder(&self, id: &str) -> Result<PathBuf> {
+    match self.world_container {
+      Some(ref folder) => {
+        validate_server_id(id)?;
+        Ok(folder.join(id))
+      }
+      None => self.server_folder(id),
+    }
+  }
+  /// Adds a player to a server's whitelist
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `name` - The player's name
+  /// * `uuid` - The player's UUID in its hyphenated form, only used while the server is offline
+  ///
+  /// # Remarks
+  /// While the server is online this goes through
+  /// [Instance::whitelist_add](struct.Instance.html#method.whitelist_add), which looks the UUID up
+  /// itself. Otherwise whitelist.json is edited directly, and created if it doesn't exist yet.
+  /// The server reads the file when it next starts.
+  pub fn whitelist_add(&mut self, id: &str, name: &str, uuid: &str) -> Result<()> {
+    validate_player_name(name)?;
+    validate_uuid(uuid)?;
+    if let Some(inst) = self.get(id) {
+      return inst.whitelist_add(name);
+    }
+    let path = self.player_list_path(id, "whitelist.json")?;
+    let mut list = PlayerList::load(&path)?;
+    list.insert(name, serde_json::json!({ "uuid": uuid, "name": name }));
+    Ok(list.save(&path)?)
+  }
+  /// Removes a player from a server's whitelist
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `name` - The player's name, matched ignoring case
+  ///
+  /// # Remarks
+  /// Goes through the console while the server is online, otherwise edits whitelist.json.
+  pub fn whitelist_remove(&mut self, id: &str, name: &str) -> Result<()> {
+    validate_player_name(name)?;
+    if let Some(inst) = self.get(id) {
+      return inst.whitelist_remove(name);
+    }
+    let path = self.player_list_path(id, "whitelist.json")?;
+    let mut list = PlayerList::load(&path)?;
+    list.remove(name);
+    Ok(list.save(&path)?)
+  }
+  /// Returns the names of the players on a server's whitelist
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Remarks
+  /// Asks the server while it is online, otherwise reads whitelist.json.
+  pub fn whitelist_list(&mut self, id: &str) -> Result<Vec<String>> {
+    if let Some(inst) = self.get(id) {
+      return inst.whitelist_list();
+    }
+    let path = self.player_list_path(id, "whitelist.json")?;
+    Ok(PlayerList::load(&path)?.names())
+  }
+  /// Makes a player an operator on a server
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `name` - The player's name
+  /// * `uuid` - The player's UUID in its hyphenated form, only used while the server is offline
+  ///
+  /// # Remarks
+  /// While the server is online this goes through [Instance::op](struct.Instance.html#method.op).
+  /// Otherwise ops.json is edited directly, giving the player permission level 4. Serbo doesn't
+  /// look UUIDs up; for online-mode servers the Mojang API at
+  /// `https://api.mojang.com/users/profiles/minecraft/<name>` returns it.
+  pub fn op(&mut self, id: &str, name: &str, uuid: &str) -> Result<()> {
+    validate_player_name(name)?;
+    validate_uuid(uuid)?;
+    if let Some(inst) = self.get(id) {
+      return inst.op(name);
+    }
+    let path = self.player_list_path(id, "ops.json")?;
+    let mut list = PlayerList::load(&path)?;
+    list.insert(
+      name,
+      serde_json::json!({ "uuid": uuid, "name": name, "level": 4, "bypassesPlayerLimit": false }),
+    );
+    Ok(list.save(&path)?)
+  }
+  /// Takes operator status away from a player on a server
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `name` - The player's name, matched ignoring case
+  ///
+  /// # Remarks
+  /// Goes through the console while the server is online, otherwise edits ops.json.
+  pub fn deop(&mut self, id: &str, name: &str) -> Result<()> {
+    validate_player_name(name)?;
+    if let Some(inst) = self.get(id) {
+      return inst.deop(name);
+    }
+    let path = self.player_list_path(id, "ops.json")?;
+    let mut list = PlayerList::load(&path)?;
+    list.remove(name);
+    Ok(list.save(&path)?)
+  }
+  /// Returns the names of a server's operators, read from its ops.json
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  pub fn list_ops(&self, id: &str) -> Result<Vec<String>> {
+    let path = self.player_list_path(id, "ops.json")?;
+    Ok(PlayerList::load(&path)?.names())
+  }
+  /// Reads the last lines written to a server's console log file
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `lines` - How many lines to read at most
+  ///
+  /// # Remarks
+  /// Only has lines for runs started with
+  /// [persist_console_log](struct.StartOptions.html#structfield.persist_console_log), and returns
+  /// an empty Vec if there are none. Works whether the server is online or not, so the output of
+  /// previous runs can be shown next to the [Instance::get](struct.Instance.html#method.get) lines
+  /// of the current one, which always starts out empty. To have them loaded on start instead, see
+  /// [preload_log_history](struct.StartOptions.html#structfield.preload_log_history).
+  pub fn load_log_history(&self, id: &str, lines: usize) -> Result<Vec<String>> {
+    let folder = self.server_folder(id)?;
+    if !folder.is_dir() {
+      return Err(Error::ServerFilesMissing(folder));
+    }
+    Ok(console::read_log_tail(&folder.join("logs"), lines)?)
+  }
+  /// The path of one of a server's JSON player lists, such as whitelist.json
+  fn player_list_path(&self, id: &str, file: &str) -> Result<PathBuf> {
+    let folder = self.server_folder(id)?;
+    if !folder.is_dir() {
+      return Err(Error::ServerFilesMissing(folder));
+    }
+    Ok(folder.join(file))
+  }
+  /// Accepts the Minecraft EULA for a server by writing `eula=true` to its eula.txt
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Remarks
+  /// Only call this once the server's operator has agreed to the EULA at
+  /// <https://aka.ms/MinecraftEULA>. Without it the server exits straight away.
+  pub fn accept_eula(&self, id: &str) -> Result<()> {
+    let folder = self.server_folder(id)?;
+    if !folder.is_dir() {
+      return Err(Error::ServerFilesMissing(folder));
+    }
+    fs::write(folder.join("eula.txt"), "eula=true\n")?;
+    Ok(())
+  }
+  /// The folder holding the files of the server with the given id, once the id is checked to be a
+  /// plain folder name
+  fn server_folder(&self, id: &str) -> Result<PathBuf> {
+    validate_server_id(id)?;
+    Ok(match self.path_resolver {
+      Some(ref resolver) => resolver(id),
+      None => self.server_files_folder.join(id),
+    })
+  }
+  /// Checks if the server is online
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// # Remarks
+  /// Queries the currently online servers, must have been launched by calling [start](struct.Manager.html#method.start)
+  pub fn is_online(&mut self, id: &str) -> bool {
+    self.get(id).is_some()
+  }
+  /// Returns the lifecycle state of a server
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Remarks
+  /// A server whose process has exited, whether it was stopped or crashed, is
+  /// [Offline](enum.ServerStatus.html#variant.Offline).
+  pub fn status(&mut self, id: &str) -> ServerStatus {
+    match self.get(id) {
+      Some(inst) if inst.is_stopping() => ServerStatus::Stopping,
+      Some(inst) if inst.is_ready() => ServerStatus::Running,
+      Some(_) => ServerStatus::Starting,
+      None => ServerStatus::Offline,
+    }
+  }
+  /// Returns the RCON port and password configured in server.properties, or None if RCON is
+  /// disabled
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// # Remarks
+  /// The password is returned as-is; take care not to log it.
+  pub fn rcon_info(&self, id: &str) -> Result<Option<(u16, String)>> {
+    rcon_settings(&self.load_properties(id)?)
+  }
+  /// Checks that RCON is reachable and accepts the configured password, without running a command
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Remarks
+  /// Returns [RconDisabled](enum.Error.html#variant.RconDisabled), [RconRefused](enum.Error.html#variant.RconRefused),
+  /// [RconAuthFailed](enum.Error.html#variant.RconAuthFailed) or [RconTimeout](enum.Error.html#variant.RconTimeout)
+  /// depending on what went wrong, so each can be reported with its own advice.
+  pub fn test_rcon(&self, id: &str) -> Result<()> {
+    let (port, password) = self.rcon_info(id)?.ok_or(Error::RconDisabled())?;
+    let host = server_address(&self.load_properties(id)?);
+    RconClient::connect(&host, port, &password, RCON_TIMEOUT)?;
+    Ok(())
+  }
+  /// Returns the query port configured in server.properties, or None if query is disabled
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  pub fn query_port(&self, id: &str) -> Result<Option<u16>> {
+    let props = self.load_properties(id)?;
+    if !property(&props, "enable-query", false)? {
+      return Ok(None);
+    }
+    Ok(Some(property(&props, "query.port", 25565)?))
+  }
+  /// Returns the level-name from server.properties, the name of the world folder the server loads
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  pub fn level_name(&self, id: &str) -> Result<String> {
+    let props = self.load_properties(id)?;
+    match props.get("level-name").map(|name| name.trim()) {
+      None | Some("") => Ok(String::from("world")),
+      Some(name) => Ok(name.to_string()),
+    }
+  }
+  /// Sets the level-name in server.properties, switching which world folder the server loads
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `name` - The name of a world folder inside the server folder
+  ///
+  /// # Remarks
+  /// If the folder doesn't exist, the server generates a new world there on its next start.
+  /// The name must be a plain folder name, not a path. Takes effect after a restart.
+  pub fn set_level_name(&mut self, id: &str, name: &str) -> Result<()> {
+    let valid = !name.trim().is_empty()
+      && name != "."
+      && name != ".."
+      && !name.contains(|c: char| c == '/' || c == '\\' || c.is_control());
+    if !valid {
+      return Err(Error::InvalidProperty(String::from("level-name")));
+    }
+    let mut props = self.load_properties(id)?;
+    props.set("level-name", name);
+    self.save_properties(id, &props)
+  }
+  /// Returns the player-idle-timeout from server.properties, in minutes. 0 means idle players are never kicked
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  pub fn player_idle_timeout(&self, id: &str) -> Result<u32> {
+    property(&self.load_properties(id)?, "player-idle-timeout", 0)
+  }
+  /// Sets the player-idle-timeout in server.properties
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `minutes` - How long a player may be idle before being kicked, or 0 to disable
+  ///
+  /// # Remarks
+  /// Takes effect after a restart.
+  pub fn set_player_idle_timeout(&mut self, id: &str, minutes: u32) -> Result<()> {
+    if minutes > i32::MAX as u32 {
+      return Err(Error::InvalidProperty(String::from("player-idle-timeout")));
+    }
+    let mut props = self.load_properties(id)?;
+    props.set("player-idle-timeout", &minutes.to_string());
+    self.save_properties(id, &props)
+  }
+  /// Returns the network-compression-threshold from server.properties: the packet size in bytes
+  /// above which packets are compressed, or None if compression is disabled
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  pub fn network_compression(&self, id: &str) -> Result<Option<u32>> {
+    let threshold: i32 = property(&self.load_properties(id)?, "network-compression-threshold", 256)?;
+    match threshold {
+      -1 => Ok(None),
+      t if t >= 0 => Ok(Some(t as u32)),
+      _ => Err(Error::InvalidProperty(String::from("network-compression-threshold"))),
+    }
+  }
+  /// Sets the network-compression-threshold in server.properties
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `threshold` - The packet size in bytes above which packets are compressed, or None to disable compression
+  ///
+  /// # Remarks
+  /// Lower thresholds save bandwidth at the cost of CPU. Takes effect after a restart.
+  pub fn set_network_compression(&mut self, id: &str, threshold: Option<u32>) -> Result<()> {
+    let value = match threshold {
+      None => -1,
+      Some(t) if t <= i32::MAX as u32 => t as i32,
+      Some(_) => return Err(Error::InvalidProperty(String::from("network-compression-threshold"))),
+    };
+    let mut props = self.load_properties(id)?;
+    props.set("network-compression-threshold", &value.to_string());
+    self.save_properties(id, &props)
+  }
+  /// Reads server.properties into a [ServerProperties](struct.ServerProperties.html), with the
+  /// commonly used settings parsed into typed fields
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let mut props = manager.get_properties("1").unwrap();
+  /// props.motd = String::from("Welcome!");
+  /// props.max_players = 10;
+  /// manager.set_properties("1", &props).unwrap();
+  /// ```
+  /// # Remarks
+  /// The port passed to [start](#method.start) is given to the server on the command line, which
+  /// takes precedence over the `port` field.
+  pub fn get_properties(&self, id: &str) -> Result<ServerProperties> {
+    ServerProperties::from_file(&self.load_properties(id)?)
+  }
+  /// Writes a [ServerProperties](struct.ServerProperties.html) back to server.properties
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `props` - The properties to write
+  ///
+  /// # Remarks
+  /// Comments and the order of existing keys in the file are preserved. Takes effect after a restart.
+  pub fn set_properties(&mut self, id: &str, props: &ServerProperties) -> Result<()> {
+    let mut file = self.load_properties(id)?;
+    props.apply(&mut file);
+    self.save_properties(id, &file)
+  }
+  fn load_properties(&self, id: &str) -> Result<PropertiesFile> {
+    let folder = self.server_folder(id)?;
+    if !folder.exists() {
+      return Err(Error::ServerFilesMissing(folder));
+    }
+    Ok(PropertiesFile::load(&folder.join("server.properties"))?)
+  }
+  fn save_properties(&self, id: &str, props: &PropertiesFile) -> Result<()> {
+    Ok(props.save(&self.server_folder(id)?.join("server.properties"))?)
+  }
+  /// Marks an online server as ready, for when it is known to be up but its readiness wasn't detected
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Remarks
+  /// Unblocks [stop](#method.stop) without killing the server. The override is recorded in the
+  /// server's console log so it shows up when the log is reviewed later.
+  pub fn force_ready(&mut self, id: &str) -> Result<()> {
+    match self.servers.get(id) {
+      Some(inst) => {
+        inst.mark_ready();
+        inst.push_console_line(String::from("[Serbo] Readiness manually overridden with force_ready"));
+        Ok(())
+      }
+      None => Err(Error::ServerOffline()),
+    }
+  }
+  /// Finds a free port in `range` and reserves it, so it won't be handed out again
+  /// # Arguments
+  /// * `range` - The ports to choose from
+  ///
+  /// # Remarks
+  /// A port is free if it isn't reserved, isn't used by any running server, and can currently be
+  /// bound. Reservations last until the port is passed to [start](#method.start) or given back with
+  /// [release_port](#method.release_port). Returns [NoPortsAvailable](enum.Error.html#variant.NoPortsAvailable)
+  /// if the range is exhausted.
+  pub fn allocate_port(&mut self, range: Range<u32>) -> Result<u32> {
+    let running_ports: HashSet<u32> = self.servers.values().map(|inst| inst.port).collect();
+    for port in range {
+      if port > u32::from(u16::MAX) || self.reserved_ports.contains(&port) || running_ports.contains(&port) {
+        continue;
+      }
+      if TcpListener::bind(("0.0.0.0", port as u16)).is_ok() {
+        self.reserved_ports.insert(port);
+        return Ok(port);
+      }
+    }
+    Err(Error::NoPortsAvailable())
+  }
+  /// Gives back a port reserved by [allocate_port](#method.allocate_port) that won't be used
+  pub fn release_port(&mut self, port: u32) {
+    self.reserved_ports.remove(&port);
+  }
+  /// Asks the OS for a free ephemeral port, skipping ones that are reserved or used by a server
+  fn ephemeral_port(&self) -> Result<u32> {
+    for _ in 0..16 {
+      //The probe listener is dropped on return, so the port is free again for java
+      let port = u32::from(TcpListener::bind(("0.0.0.0", 0))?.local_addr()?.port());
+      let taken = self.reserved_ports.contains(&port) || self.servers.values().any(|inst| inst.port == port);
+      if !taken {
+        return Ok(port);
+      }
+    }
+    Err(Error::NoPortsAvailable())
+  }
+  /// Launches a server with the default [StartOptions](struct.StartOptions.html)
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `port` - The port that the server should be started on, or 0 to pick a free one
+  /// # Remarks
+  /// Returns [EulaNotAccepted](enum.Error.html#variant.EulaNotAccepted) without launching if the
+  /// server's eula.txt does not contain `eula=true`, as the server would immediately exit. See
+  /// [accept_eula](#method.accept_eula).
+  pub fn start(&mut self, id: &str, port: u32) -> Result<u32> {
+    self.start_with(id, port, &StartOptions::default())
+  }
+  /// Launches a server
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `port` - The port that the server should be started on, or 0 to pick a free one
+  /// * `options` - How the server's JVM should be launched
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let options = serbo::StartOptions {
+  ///   max_heap_mb: 8192,
+  ///   ..Default::default()
+  /// };
+  /// manager.start_with("1", 25565, &options).unwrap();
+  /// ```
+  /// Missing server files are reported before java is launched, so this runs without one installed:
+  /// ```
+  /// use serbo::{Error, Manager, StartOptions};
+  ///
+  /// let servers = std::env::temp_dir().join(format!("serbo-start-with-{}", std::process::id()));
+  /// let mut manager = Manager::new(servers.to_str().unwrap(), "versions");
+  /// let options = StartOptions {
+  ///   java_path: "java-that-is-not-installed".into(),
+  ///   ..Default::default()
+  /// };
+  /// //No folder for the server
+  /// match manager.start_with("1", 0, &options) {
+  ///   Err(Error::ServerFilesMissing(path)) => assert_eq!(path, servers.join("1")),
+  ///   other => panic!("{:?}", other),
+  /// }
+  /// //A folder, but no jar in it
+  /// std::fs::create_dir_all(servers.join("1")).unwrap();
+  /// match manager.start_with("1", 0, &options) {
+  ///   Err(Error::ServerFilesMissing(path)) => assert_eq!(path, servers.join("1").join("server.jar")),
+  ///   other => panic!("{:?}", other),
+  /// }
+  /// std::fs::remove_dir_all(&servers).unwrap();
+  /// ```
+  /// # Remarks
+  /// Returns [InvalidArgument](enum.Error.html#variant.InvalidArgument) if the heap sizes are zero or
+  /// the minimum is larger than the maximum, as the JVM would fail to launch, or if the console log
+  /// capacity is zero. Returns [ServerFilesMissing](enum.Error.html#variant.ServerFilesMissing)
+  /// if the server's folder or its [jar](struct.StartOptions.html#structfield.jar_name) doesn't
+  /// exist. Returns [PortInUse](enum.Error.html#variant.PortInUse) without launching if
+  /// the port is already bound, unless [check_port](struct.StartOptions.html#structfield.check_port)
+  /// is turned off.
+  ///
+  /// Returns the port the server was started on, which is also kept in the Instance's
+  /// [port](struct.Instance.html#structfield.port). With port 0 a free ephemeral port is chosen by
+  /// the OS. It is only free at the moment it is chosen, so another program could still take it
+  /// before the server binds it, which then fails the start with
+  /// [PortInUse](enum.Error.html#variant.PortInUse).
+  pub fn start_with(&mut self, id: &str, port: u32, options: &StartOptions) -> Result<u32> {
+    if options.min_heap_mb == 0 || options.min_heap_mb > options.max_heap_mb {
+      return Err(Error::InvalidArgument(format!(
+        "heap sizes must be non-zero with min <= max, got min {}M and max {}M",
+        options.min_heap_mb, options.max_heap_mb
+      )));
+    }
+    if options.console_log_capacity == 0 {
+      return Err(Error::InvalidArgument(String::from("console_log_capacity must be at least 1")));
+    }
+    if let Some(inst) = self.servers.get_mut(id) {
+      if inst.is_valid()? {
+        return Err(Error::ServerAlreadyOnline());
+      }
+      //The previous run exited on its own, clear it out before starting again
+      inst.join_threads();
+      self.remove_exited(id);
+    }
+    let folder = self.server_folder(id)?;
+    //Java would only report these by exiting, so catch them before spawning it
+    if !folder.is_dir() {
+      return Err(Error::ServerFilesMissing(folder));
+    }
+    let jar = folder.join(&options.jar_name);
+    if !jar.is_file() {
+      return Err(Error::ServerFilesMissing(jar));
+    }
+    if options.accept_eula && !eula_accepted(&folder) {
+      self.accept_eula(id)?;
+    }
+    if !eula_accepted(&folder) {
+      return Err(Error::EulaNotAccepted());
+    }
+    let port = match port {
+      0 => self.ephemeral_port()?,
+      port => port,
+    };
+    if options.check_port {
+      if port > u32::from(u16::MAX) {
+        return Err(Error::InvalidArgument(format!("{} is not a valid port", port)));
+      }
+      //The probe listener is dropped straight away, so the port is free again for java
+      if TcpListener::bind(("0.0.0.0", port as u16)).is_err() {
+        return Err(Error::PortInUse(port));
+      }
+    }
+    let mut command = Command::new(&options.java_path);
+    command
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .arg(format!("-Xmx{}M", options.max_heap_mb))
+      .arg(format!("-Xms{}M", options.min_heap_mb))
+      .args(&options.jvm_args)
+      .arg("-jar")
+      .arg(&options.jar_name)
+      .arg("nogui")
+      .current_dir(&folder);
+    let port_overridden = options.server_args.iter().any(|arg| arg == "--port" || arg.starts_with("--port="));
+    if !port_overridden {
+      command.arg("--port").arg(port.to_string());
+    }
+    if self.world_container.is_some() {
+      let universe = self.world_folder(id)?;
+      fs::create_dir_all(&universe)?;
+      //The server runs in its own folder, so a relative container would be looked up from there
+      command.arg("--universe").arg(fs::canonicalize(universe)?);
+    }
+    command.args(&options.server_args);
+    //Read before this run starts appending to the same file
+    let history = console::read_log_tail(&folder.join("logs"), options.preload_log_history)?;
+    let mut log_file = if options.persist_console_log {
+      Some(LogFile::open(&folder.join("logs"), options.console_log_capacity)?)
+    } else {
+      None
+    };
+    let child = command.spawn()?;
+    let spawned = Instant::now();
+    let started_at = SystemTime::now();
+    self.reserved_ports.remove(&port);
+    let mut serv_inst = Instance {
+      server_process: child,
+      stdout_join: None,
+      stdin_join: None,
+      stderr_join: None,
+      console_log: Arc::new(Mutex::new(LogBuffer::new(options.console_log_capacity))),
+      stdin_queue: Arc::new(CommandQueue::new()),
+      thread_cond: Arc::new(RwLock::new(true)),
+      starting: Arc::new(RwLock::new(true)),
+      stopping: Arc::new(RwLock::new(false)),
+      failure: Arc::new(RwLock::new(None)),
+      subscribers: Arc::new(Mutex::new(Vec::new())),
+      gc_log: Arc::new(Mutex::new(LogBuffer::new(options.console_log_capacity))),
+      stderr_log: Arc::new(Mutex::new(LogBuffer::new(options.console_log_capacity))),
+      online_players: Arc::new(Mutex::new(HashSet::new())),
+      log_bytes: Arc::new(AtomicUsize::new(0)),
+      stdin_closed: Arc::new(RwLock::new(false)),
+      identity: None,
+      rcon: None,
+      folder,
+      started: false,
+      spawned,
+      started_at,
+      options: options.clone(),
+      restarts: 0,
+      cpu_sample: Mutex::new(None),
+      schedule: None,
+      history,
+      exit: Arc::new(Mutex::new(ProcessExit::default())),
+      port
+    };
+    let stdout = match serv_inst.server_process.stdout.take() {
+      Some(e) => e,
+      None => return Err(Error::ThreadError("stdout".to_string())),
+    };
+    let stdin = match serv_inst.server_process.stdin.take() {
+      Some(e) => e,
+      None => return Err(Error::ThreadError("stdin".to_string())),
+    };
+    let stderr = match serv_inst.server_process.stderr.take() {
+      Some(e) => e,
+      None => return Err(Error::ThreadError("stderr".to_string())),
+    };
+
+    let starting_lock = serv_inst.starting.clone();
+    let ready_grace = self.ready_grace;
+    let stopping_lock = serv_inst.stopping.clone();
+    let failure_lock = serv_inst.failure.clone();
+    let patterns = self.console_patterns.clone();
+    let subscribers = serv_inst.subscribers.clone();
+    let all_subscribers = self.all_subscribers.clone();
+    let server_id = id.to_string();
+    let gc_arc = serv_inst.gc_log.clone();
+    let players_arc = serv_inst.online_players.clone();
+    let log_bytes = serv_inst.log_bytes.clone();
+    let separate_gc_log = self.separate_gc_log;
+    let stdin_closed = serv_inst.stdin_closed.clone();
+    let stdout_arc = serv_inst.console_log.clone();
+    let stdin_arc = serv_inst.stdin_queue.clone();
+    let cond_reader1 = serv_inst.thread_cond.clone();
+    let cond_reader2 = serv_inst.thread_cond.clone();
+    let cond_reader3 = serv_inst.thread_cond.clone();
+    let stderr_arc = serv_inst.stderr_log.clone();
+    let stderr_bytes = serv_inst.log_bytes.clone();
+    let exit_lock = serv_inst.exit.clone();
+    let pid = serv_inst.server_process.id();
+
+    let stdout_thread_handle = thread::spawn(move || {
+      let mut reader = BufReader::new(stdout).lines();
+      let output_ended = loop {
+        let r1 = cond_reader1.read().unwrap();
+        if !*r1{
+          break false;
+        }
+        drop(r1);
+        //The pipe reaches its end once the process has exited, which ends the thread even if the
+        //flag is never cleared
+        let a = match reader.next() {
+          Some(Ok(a)) => a,
+          //A line that isn't valid UTF-8 is skipped
+          Some(Err(ref e)) if e.kind() == io::ErrorKind::InvalidData => continue,
+          Some(Err(_)) | None => break true,
+        };
+        log_bytes.fetch_add(a.len(), Ordering::Relaxed);
+        if separate_gc_log && is_gc_log_line(&a) {
+          if let Some(evicted) = gc_arc.lock().unwrap().push(a) {
+            log_bytes.fetch_sub(evicted.len(), Ordering::Relaxed);
+          }
+          continue;
+        }
+        if patterns.ready.is_match(&a) {
+          if ready_grace == Duration::from_secs(0) {
+            let mut g = starting_lock.write().unwrap();
+            *g = false;
+          } else {
+            //Clear the flag later without holding up the output
+            let starting_lock = starting_lock.clone();
+            thread::spawn(move || {
+              thread::sleep(ready_grace);
+              let mut g = starting_lock.write().unwrap();
+              *g = false;
+            });
+          }
+        }
+        if let Some((name, joined)) = player_event(&a) {
+          let mut players = players_arc.lock().unwrap();
+          if joined {
+            players.insert(name);
+          } else {
+            players.remove(&name);
+          }
+        }
+        if patterns.stopping.is_match(&a) {
+          let mut g = stopping_lock.write().unwrap();
+          *g = true;
+        }
+        let failure = if patterns.eula.is_match(&a) {
+          Some(Failure::Eula)
+        } else if patterns.oom.is_match(&a) {
+          Some(Failure::OutOfMemory)
+        } else if patterns.port_fail.is_match(&a) {
+          Some(Failure::PortBind)
+        } else {
+          None
+        };
+        if failure.is_some() {
+          let mut g = failure_lock.write().unwrap();
+          *g = failure;
+        }
+
+        //Dropped subscriptions are pruned here
+        subscribers.lock().unwrap().retain(|tx| tx.send(a.clone()).is_ok());
+        all_subscribers.lock().unwrap().retain(|tx| tx.send((server_id.clone(), a.clone())).is_ok());
+        let write_failed = match log_file {
+          Some(ref mut file) => file.write_line(&a).is_err(),
+          None => false,
+        };
+        if write_failed {
+          //Keep the server running without its copy on disk, e.g. when the disk is full
+          log_file = None;
+        }
+        if let Some(evicted) = stdout_arc.lock().unwrap().push(a) {
+          log_bytes.fetch_sub(evicted.len(), Ordering::Relaxed);
+        }
+      };
+      //Crashes are noticed here, without anyone having to poll for them
+      if output_ended && watch_exit(pid, &exit_lock, &cond_reader1) {
+        let ready = !*starting_lock.read().unwrap();
+        let crashed = ready && !*stopping_lock.read().unwrap() && *cond_reader1.read().unwrap();
+        if crashed {
+          let mut exit = exit_lock.lock().unwrap();
+          exit.crashed = true;
+          let line = match exit.status {
+            Some(status) => format!("[Serbo] Server crashed, {}", status),
+            None => String::from("[Serbo] Server crashed"),
+          };
+          drop(exit);
+          subscribers.lock().unwrap().retain(|tx| tx.send(line.clone()).is_ok());
+          all_subscribers.lock().unwrap().retain(|tx| tx.send((server_id.clone(), line.clone())).is_ok());
+          log_bytes.fetch_add(line.len(), Ordering::Relaxed);
+          if let Some(evicted) = stdout_arc.lock().unwrap().push(line) {
+            log_bytes.fetch_sub(evicted.len(), Ordering::Relaxed);
+          }
+        }
+      }
+      //Dropping the senders lets blocked subscriptions know no more lines are coming
+      subscribers.lock().unwrap().clear();
+    });
+
+    //The writer thread is the sole owner of the server's stdin, which stays open for as long as
+    //the thread runs. Some server wrappers exit as soon as stdin closes, so the thread only
+    //finishes once shutdown has been requested and the queue (including /stop) is drained, or
+    //once the server has already closed the pipe itself.
+    let stdin_thread_handle = thread::spawn(move || {
+      let mut writer = BufWriter::new(stdin);
+      while let Some(batch) = stdin_arc.next_batch(&cond_reader2) {
+        let write_result = batch
+          .iter()
+          .try_for_each(|x| writeln!(writer, "{}", x).and_then(|_| writer.flush()));
+        //The server closed its end of the pipe, nothing more can be written
+        if write_result.is_err() {
+          let mut closed = stdin_closed.write().unwrap();
+          *closed = true;
+          break;
+        }
+      }
+    });
+    //Without a reader the stderr pipe fills up and blocks the server on its next warning
+    let stderr_thread_handle = thread::spawn(move || {
+      let mut reader = BufReader::new(stderr).lines();
+      loop {
+        if !*cond_reader3.read().unwrap() {
+          break;
+        }
+        match reader.next() {
+          Some(Ok(a)) => {
+            stderr_bytes.fetch_add(a.len(), Ordering::Relaxed);
+            if let Some(evicted) = stderr_arc.lock().unwrap().push(a) {
+              stderr_bytes.fetch_sub(evicted.len(), Ordering::Relaxed);
+            }
+          }
+          Some(Err(_)) => continue,
+          None => break,
+        }
+      }
+    });
+    serv_inst.stdout_join = Some(stdout_thread_handle);
+    serv_inst.stdin_join = Some(stdin_thread_handle);
+    serv_inst.stderr_join = Some(stderr_thread_handle);
+    if let Err(e) = serv_inst.process_check() {
+      //The process died straight away, don't leave its threads running
+      serv_inst.join_threads();
+      if let Ok(status) = serv_inst.reap() {
+        self.exit_statuses.insert(id.to_string(), status);
+      }
+      return Err(e);
+    }
+    if let Some(fallback) = self.ready_fallback {
+      let starting_lock = serv_inst.starting.clone();
+      let cond_reader3 = serv_inst.thread_cond.clone();
+      thread::spawn(move || {
+        let deadline = Instant::now() + fallback;
+        while Instant::now() < deadline {
+          if !*cond_reader3.read().unwrap() {
+            return;
+          }
+          thread::sleep(Duration::from_millis(100));
+        }
+        let mut g = starting_lock.write().unwrap();
+        *g = false;
+      });
+    }
+    self.servers.insert(id.to_string(), serv_inst);
+    Ok(port)
+  }
+  /// Stops a server
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Remarks
+  /// A server that is still starting is only stopped if it turns out to be up anyway, meaning RCON
+  /// accepts a login or the console answers a `list` command. This covers servers whose readiness
+  /// line was never recognized. Otherwise returns [ServerStillStarting](enum.Error.html#variant.ServerStillStarting).
+  /// A server whose process has already exited is cleaned up and Ok is returned.
+  ///
+  /// If the server hasn't exited within the [stop timeout](#method.set_stop_timeout) it is killed
+  /// and [StopTimeout](enum.Error.html#variant.StopTimeout) is returned. Either way it is no
+  /// longer online afterwards.
+  pub fn stop(&mut self, id: &str) -> Result<()> {
+    let deadline = Instant::now() + self.stop_timeout_of(id);
+    self.request_stop(id, false)?;
+    self.finish_stop(id, deadline)
+  }
+  /// Stops every online server, returning the result of stopping each along with its id
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use std::sync::atomic::{AtomicBool, Ordering};
+  /// use std::sync::Arc;
+  ///
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// //Set from a ctrl-c or SIGTERM handler, e.g. with the ctrlc crate:
+  /// //ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst))
+  /// let shutdown = Arc::new(AtomicBool::new(false));
+  /// while !shutdown.load(Ordering::SeqCst) {
+  ///   std::thread::sleep(std::time::Duration::from_millis(100));
+  /// }
+  /// for (id, result) in manager.stop_all() {
+  ///   if let Err(e) = result {
+  ///     eprintln!("Failed to stop {}: {}", id, e);
+  ///   }
+  /// }
+  /// ```
+  /// # Remarks
+  /// Every server is sent `/stop` before any is waited for, so they save in parallel and the whole
+  /// call takes about the longest [stop timeout](#method.set_stop_timeout) at most. A server that fails to
+  /// stop doesn't keep the others from being stopped, its error is returned like
+  /// [stop](#method.stop) would. Servers that are still starting are sent `/stop` as well, which
+  /// they run once they finish loading; any that haven't exited by the deadline are killed and
+  /// reported with [StopTimeout](enum.Error.html#variant.StopTimeout), so no server is left running.
+  pub fn stop_all(&mut self) -> Vec<(String, Result<()>)> {
+    let now = Instant::now();
+    let mut ids: Vec<String> = self.servers.keys().cloned().collect();
+    ids.sort();
+    let requested: Vec<(String, Instant, Result<()>)> = ids
+      .into_iter()
+      .map(|id| {
+        let deadline = now + self.stop_timeout_of(&id);
+        let result = self.request_stop(&id, true);
+        (id, deadline, result)
+      })
+      .collect();
+    requested
+      .into_iter()
+      .map(|(id, deadline, result)| {
+        let result = result.and_then(|()| self.finish_stop(&id, deadline));
+        (id, result)
+      })
+      .collect()
+  }
+  /// Returns how long a server gets to exit after `/stop`, its own stop timeout if it was started
+  /// with one
+  fn stop_timeout_of(&self, id: &str) -> Duration {
+    match self.servers.get(id).and_then(|inst| inst.options.stop_timeout) {
+      Some(timeout) => timeout,
+      None => self.stop_timeout,
+    }
+  }
+  /// Sends `/stop` to a server, the first half of [stop](#method.stop). A server whose process has
+  /// already exited is cleaned up instead. Unless `even_if_starting` is set, a server that is still
+  /// starting and can't be shown to read its console yet is refused with ServerStillStarting
+  fn request_stop(&mut self, id: &str, even_if_starting: bool) -> Result<()> {
+    let unconfirmed = match self.servers.get(id) {
+      Some(inst) => *inst.starting.read().unwrap() && !inst.started && !even_if_starting,
+      None => return Err(Error::ServerOffline()),
+    };
+    let rcon_reachable = unconfirmed && self.test_rcon(id).is_ok();
+    if let Some(inst) = self.servers.get_mut(id) {
+      if !inst.is_valid()? {
+        inst.join_threads();
+        self.remove_exited(id);
+        return Ok(());
+      }
+      if !unconfirmed || rcon_reachable || inst.responds_to_console() {
+        return inst.stop();
+      }
+      return Err(Error::ServerStillStarting());
+    }
+    Err(Error::ServerOffline())
+  }
+  /// Waits for a server sent `/stop` by request_stop to exit, killing it once `deadline` passes
+  fn finish_stop(&mut self, id: &str, deadline: Instant) -> Result<()> {
+    let result = match self.servers.get_mut(id) {
+      Some(inst) => inst.wait_or_kill(deadline.saturating_duration_since(Instant::now())),
+      //Already cleaned up by request_stop
+      None => return Ok(()),
+    };
+    self.remove_exited(id);
+    result
+  }
+  /// Removes a server whose process has exited, remembering how it exited for last_exit_status
+  fn remove_exited(&mut self, id: &str) {
+    if let Some(mut inst) = self.servers.remove(id) {
+      if let Ok(Some(status)) = inst.try_reap() {
+        self.exit_statuses.insert(id.to_string(), status);
+      }
+    }
+  }
+  /// Returns how a server's process exited the last time it stopped or crashed, or None if it
+  /// hasn't exited since the Manager was created
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// manager.stop("1").unwrap();
+  /// if let Some(status) = manager.last_exit_status("1") {
+  ///   match status.code() {
+  ///     Some(code) => println!("Exited with code {}", code),
+  ///     None => println!("Killed by a signal"),
+  ///   }
+  /// }
+  /// ```
+  /// # Remarks
+  /// Recorded when a server is cleaned up by [stop](#method.stop), [stop_all](#method.stop_all),
+  /// [recover_crashed](#method.recover_crashed) or a new [start](#method.start) after it exited,
+  /// and kept while the server runs again, until that run exits too. On Unix the signal that
+  /// killed the process, such as SIGKILL after a [stop timeout](#method.set_stop_timeout), is
+  /// available from `std::os::unix::process::ExitStatusExt::signal`.
+  pub fn last_exit_status(&self, id: &str) -> Option<ExitStatus> {
+    let current = self.servers.get(id).and_then(|inst| inst.exit.lock().unwrap().status);
+    current.or_else(|| self.exit_statuses.get(id).copied())
+  }
+  /// Finds servers that crashed after finishing starting, and restarts those started with
+  /// [auto_restart](struct.StartOptions.html#structfield.auto_restart)
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let options = serbo::StartOptions {
+  ///   auto_restart: true,
+  ///   ..Default::default()
+  /// };
+  /// manager.start_with("1", 25565, &options).unwrap();
+  /// loop {
+  ///   for crash in manager.recover_crashed() {
+  ///     println!("{} exited with {}", crash.id, crash.status);
+  ///   }
+  ///   std::thread::sleep(std::time::Duration::from_secs(5));
+  /// }
+  /// ```
+  /// # Remarks
+  /// A crash is the process exiting without being stopped, either by [stop](#method.stop) or from
+  /// in game. Servers that exit while starting aren't counted, as restarting them is unlikely to
+  /// help. Crashes are noticed by the server's stdout thread as soon as the process exits: its
+  /// exit status is recorded for [last_exit_status](#method.last_exit_status) and a
+  /// `[Serbo] Server crashed, exit status: 1` style line is added to the console log and sent to
+  /// [subscriptions](struct.Instance.html#method.subscribe), without anything having to poll.
+  ///
+  /// This call then does the restarting. Each crashed server is reported once and restarted on the
+  /// same port with the same options, until it has been restarted
+  /// [max_restarts](struct.StartOptions.html#structfield.max_restarts) times, after which it is
+  /// left offline. Call it periodically, or when a subscription reports a crash.
+  pub fn recover_crashed(&mut self) -> Vec<Crash> {
+    let crashed: Vec<String> = self
+      .servers
+      .iter_mut()
+      .filter_map(|(id, inst)| if inst.crashed() { Some(id.clone()) } else { None })
+      .collect();
+    let mut crashes = Vec::new();
+    for id in crashed {
+      let mut inst = match self.servers.remove(&id) {
+        Some(inst) => inst,
+        None => continue,
+      };
+      //join_threads drops the schedule, so keep its config to set it up again
+      let config = inst.schedule.as_ref().map(|schedule| schedule.config().clone());
+      inst.join_threads();
+      let status = match inst.reap() {
+        Ok(status) => status,
+        Err(_) => continue,
+      };
+      self.exit_statuses.insert(id.clone(), status);
+      let restart = if inst.options.auto_restart && inst.restarts < inst.options.max_restarts {
+        let restarts = inst.restarts + 1;
+        let result = self.start_with(&id, inst.port, &inst.options);
+        if let Some(new_inst) = self.servers.get_mut(&id) {
+          new_inst.restarts = restarts;
+        }
+        if let (Ok(_), Some(config)) = (&result, config) {
+          let _ = self.apply_schedule(&id, config);
+        }
+        Some(result)
+      } else {
+        None
+      };
+      crashes.push(Crash { id, status, restart });
+    }
+    crashes
+  }
+  /// Calls `callback` with a sample of an online server's metrics every `interval`
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  /// * `interval` - How long to wait between samples
+  /// * `callback` - Called with each sample, on the sampler's own thread
+  /// # Examples
+  /// ```no_run
+  /// use std::time::Duration;
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// let metrics = manager
+  ///   .start_metrics("1", Duration::from_secs(10), Box::new(|sample| println!("{:?}", sample)))
+  ///   .unwrap();
+  /// //Sampling stops when the handle is dropped
+  /// drop(metrics);
+  /// ```
+  /// # Remarks
+  /// See [ServerMetrics](struct.ServerMetrics.html) for what is sampled. Nothing is sent to the
+  /// server, so sampling often doesn't fill its console. The sampler stops when the returned
+  /// [MetricsHandle](struct.MetricsHandle.html) is dropped or once the server stops or crashes, so
+  /// it has to be started again after a restart. Returns
+  /// [ServerOffline](enum.Error.html#variant.ServerOffline) if the server isn't running.
+  pub fn start_metrics(
+    &mut self,
+    id: &str,
+    interval: Duration,
+    callback: Box<dyn Fn(ServerMetrics) + Send>,
+  ) -> Result<MetricsHandle> {
+    let inst = self.get(id).ok_or(Error::ServerOffline())?;
+    let sampler = Sampler {
+      pid: inst.server_process.id(),
+      spawned: inst.spawned,
+      exit: inst.exit.clone(),
+      alive: inst.thread_cond.clone(),
+      stopping: inst.stopping.clone(),
+      online_players: inst.online_players.clone(),
+      console_log: inst.console_log.clone(),
+    };
+    Ok(MetricsHandle::start(sampler, interval, callback))
+  }
+  /// Schedules restarts and autosaves for an online server
+  /// # Arguments
+  /// * `id` - The id that represents the requested server
+  ///
+  /// # Remarks
+  /// See [ScheduleBuilder](struct.ScheduleBuilder.html). Each interval runs on its own timer
+  /// thread, which sends `save-all` when an autosave is due. Before a restart the players are
+  /// warned with `say` and the world is saved, then the server waits for
+  /// [run_scheduled_restarts](#method.run_scheduled_restarts) to restart it. The timers end when
+  /// the server stops, so a schedule has to be applied again after a manual restart. Servers
+  /// restarted by the schedule or by [recover_crashed](#method.recover_crashed) keep theirs.
+  pub fn schedule(&mut self, id: &str) -> ScheduleBuilder<'_> {
+    ScheduleBuilder::new(self, id)
+  }
+  fn apply_schedule(&mut self, id: &str, config: ScheduleConfig) -> Result<()> {
+    let inst = self.get(id).ok_or(Error::ServerOffline())?;
+    //Ends the old timers before the new ones start
+    inst.schedule = None;
+    let signals = TimerSignals {
+      cancel: Arc::new(RwLock::new(false)),
+      alive: inst.thread_cond.clone(),
+      stopping: inst.stopping.clone(),
+    };
+    inst.schedule = Some(Schedule::start(config, inst.stdin_queue.clone(), signals));
+    Ok(())
+  }
+  /// Restarts the servers whose [scheduled](#method.schedule) restart is due, on the same port
+  /// and with the same options. Returns the id of each restarted server along with the result of
+  /// starting it again
+  ///
+  /// # Remarks
+  /// Call this periodically, e.g. every second, as a due server keeps running until it is called.
+  /// A server that had to be killed for taking longer than the
+  /// [stop timeout](#method.set_stop_timeout) is still started again.
+  pub fn run_scheduled_restarts(&mut self) -> Vec<(String, Result<u32>)> {
+    let due: Vec<String> = self
+      .servers
+      .iter()
+      .filter_map(|(id, inst)| match inst.schedule {
+        Some(ref schedule) if schedule.restart_due() => Some(id.clone()),
+        _ => None,
+      })
+      .collect();
+    let mut restarted = Vec::new();
+    for id in due {
+      let (port, options, config) = match self.servers.get_mut(&id) {
+        Some(inst) => match inst.schedule.take() {
+          Some(schedule) => (inst.port, inst.options.clone(), schedule.config().clone()),
+          None => continue,
+        },
+        None => continue,
+      };
+      let result = match self.stop(&id) {
+        Ok(()) | Err(Error::StopTimeout()) => self.start_with(&id, port, &options),
+        Err(e) => Err(e),
+      };
+      if result.is_ok() {
+        let _ = self.apply_schedule(&id, config);
+      }
+      restarted.push((id, result));
+    }
+    restarted
+  }
+}
+
+/// Options controlling how [start_with](struct.Manager.html#method.start_with) launches a server
+#[derive(Debug, Clone)]
+pub struct StartOptions {
+  /// The initial JVM heap size in megabytes, passed as `-Xms`. Defaults to 1024
+  pub min_heap_mb: u32,
+  /// The maximum JVM heap size in megabytes, passed as `-Xmx`. Defaults to 4096
+  pub max_heap_mb: u32,
+  /// The java executable to run, either a full path or a name looked up on `PATH`. Defaults to `java`
+  pub java_path: PathBuf,
+  /// The jar to launch, relative to the server's folder, e.g. `fabric-server-launch.jar`. Defaults to `server.jar`
+  pub jar_name: String,
+  /// The most lines kept in the console log, and separately in the GC log, before the oldest are
+  /// dropped. Defaults to 10000
+  pub console_log_capacity: usize,
+  /// Whether to accept the EULA with [accept_eula](struct.Manager.html#method.accept_eula) before
+  /// launching, if it hasn't been accepted yet. Defaults to false
+  pub accept_eula: bool,
+  /// Whether [recover_crashed](struct.Manager.html#method.recover_crashed) starts the server again
+  /// after it crashes. Defaults to false
+  pub auto_restart: bool,
+  /// How many times the server is restarted after crashing before it is left offline. Defaults to 3
+  pub max_restarts: u32,
+  /// Whether console lines are also written to `logs/serbo-console.log` in the server's folder, to
+  /// be read back with [load_log_history](struct.Manager.html#method.load_log_history). The file is
+  /// rotated every [console_log_capacity](#structfield.console_log_capacity) lines, keeping one
+  /// previous file. Defaults to false
+  pub persist_console_log: bool,
+  /// How many lines of `logs/serbo-console.log` from previous runs to load on start, to be read
+  /// with [get_history](struct.Instance.html#method.get_history). Only runs started with
+  /// [persist_console_log](#structfield.persist_console_log) wrote lines there. Defaults to 0,
+  /// which loads nothing
+  pub preload_log_history: usize,
+  /// Whether to check that the port can be bound before launching, returning
+  /// [PortInUse](enum.Error.html#variant.PortInUse) if it can't. Defaults to true; turn it off when
+  /// ports are managed outside of Serbo
+  pub check_port: bool,
+  /// Extra arguments for the JVM, such as `-D` system properties or GC flags. They are placed
+  /// after the heap sizes and before `-jar`, so they configure java rather than the server.
+  /// Defaults to none
+  pub jvm_args: Vec<String>,
+  /// Extra arguments for the server, such as `--world`. They are placed after `nogui` and the
+  /// port, so they are read by the server rather than java. Passing `--port` here replaces the
+  /// one Serbo adds, though the port given to [start_with](struct.Manager.html#method.start_with)
+  /// is still the one checked and reported. Defaults to none
+  pub server_args: Vec<String>,
+  /// How long [stop](struct.Manager.html#method.stop), [stop_all](struct.Manager.html#method.stop_all)
+  /// and dropping the [Instance](struct.Instance.html) wait for the server to exit after `/stop`
+  /// before killing it, e.g. a minute for a heavy modpack. Defaults to None, which uses the
+  /// Manager's [stop timeout](struct.Manager.html#method.set_stop_timeout), or 10 seconds when the
+  /// Instance is dropped
+  pub stop_timeout: Option<Duration>,
+}
+
+impl Default for StartOptions {
+  fn default() -> Self {
+    StartOptions {
+      min_heap_mb: 1024,
+      max_heap_mb: 4096,
+      java_path: PathBuf::from("java"),
+      jar_name: String::from("server.jar"),
+      console_log_capacity: 10000,
+      accept_eula: false,
+      auto_restart: false,
+      max_restarts: 3,
+      persist_console_log: false,
+      preload_log_history: 0,
+      check_port: true,
+      jvm_args: Vec::new(),
+      server_args: Vec::new(),
+      stop_timeout: None,
+    }
+  }
+}
+
+/// Options controlling how [create](struct.Manager.html#method.create) sets up a new server
+#[derive(Debug, Clone)]
+pub struct CreateOptions {
+  /// The name of a folder in the version folder to copy, e.g. `1.16.1`
+  pub version: String,
+  /// The `allow-flight` property, letting players fly in survival without being kicked. None
+  /// keeps the version's value
+  pub allow_flight: Option<bool>,
+  /// The `force-gamemode` property, putting players back in the default gamemode when they join.
+  /// None keeps the version's value
+  pub force_gamemode: Option<bool>,
+  /// The `allow-nether` property, letting players travel to the nether. None keeps the version's value
+  pub allow_nether: Option<bool>,
+  /// A world folder to copy in as the server's world, such as a singleplayer save or a prebuilt
+  /// map. It must contain a level.dat. None keeps the version's world, if it has one
+  pub world_import: Option<PathBuf>,
+}
+
+impl CreateOptions {
+  /// Options that create a server from `version` without changing any of its properties
+  pub fn new(version: &str) -> CreateOptions {
+    CreateOptions {
+      version: version.to_string(),
+      allow_flight: None,
+      force_gamemode: None,
+      allow_nether: None,
+      world_import: None,
+    }
+  }
+}
+
+/// A server found to have crashed by [recover_crashed](struct.Manager.html#method.recover_crashed)
+#[derive(Debug)]
+pub struct Crash {
+  /// The id that represents the server
+  pub id: String,
+  /// How the server process exited
+  pub status: ExitStatus,
+  /// The result of starting the server again, or None if it wasn't restarted
+  pub restart: Option<Result<u32>>,
+}
+
+/// Represents a currently online server.
+/// Created by calling [start](struct.Manager.html#method.start) from a [Manager](struct.Manager.html)
+///
+/// Dropping an Instance whose server is still running sends `/stop` and kills the process if it
+/// hasn't exited within its [stop_timeout](struct.StartOptions.html#structfield.stop_timeout), 10
+/// seconds unless set, so no java process is left behind. Stopping the server with
+/// [Manager::stop](struct.Manager.html#method.stop) is still preferred, as it waits as long as the
+/// server needs to save.
+#[derive(Debug)]
+pub struct Instance {
+  pub server_process: Child,
+  stdout_join: Option<thread::JoinHandle<()>>,
+  stdin_join: Option<thread::JoinHandle<()>>,
+  stderr_join: Option<thread::JoinHandle<()>>,
+  console_log: Arc<Mutex<LogBuffer>>,
+  stdin_queue: Arc<CommandQueue>,
+  thread_cond: Arc<RwLock<bool>>,
+  starting: Arc<RwLock<bool>>,
+  stopping: Arc<RwLock<bool>>,
+  failure: Arc<RwLock<Option<Failure>>>,
+  subscribers: Arc<Mutex<Vec<Sender<String>>>>,
+  gc_log: Arc<Mutex<LogBuffer>>,
+  stderr_log: Arc<Mutex<LogBuffer>>,
+  online_players: Arc<Mutex<HashSet<String>>>,
+  log_bytes: Arc<AtomicUsize>,
+  stdin_closed: Arc<RwLock<bool>>,
+  identity: Option<ServerIdentity>,
+  rcon: Option<RconClient>,
+  folder: PathBuf,
+  started: bool,
+  spawned: Instant,
+  started_at: SystemTime,
+  options: StartOptions,
+  restarts: u32,
+  cpu_sample: Mutex<Option<(Instant, f64)>>,
+  schedule: Option<Schedule>,
+  history: Vec<String>,
+  exit: Arc<Mutex<ProcessExit>>,
+  /// The port the server was started on, the one chosen by the OS if it was started with port 0
+  pub port: u32,
+}
+
+impl Instance {
+  /// Stops the server, killing the server process and the stdin
+  /// and stdout threads
+  pub fn stop(&mut self) -> Result<()> {
+    let _ = self.process_check();
+    //Marked here too, so the exit that follows isn't taken for a crash if the server never says so
+    *self.stopping.write().unwrap() = true;
+    match self.send(String::from("/stop")) {
+      //A closed stdin means the server is already on its way down
+      Ok(()) | Err(Error::StdinClosed()) => Ok(()),
+      Err(e) => Err(e),
+    }
+  }
+  /// Freezes the server process with SIGSTOP, freeing its CPU without unloading it
+  ///
+  /// # Remarks
+  /// The process keeps all of its memory while suspended, and connected players will time out
+  /// since nothing answers the network. Call [resume](#method.resume) to continue it.
+  /// Returns [ServerProcessExited](enum.Error.html#variant.ServerProcessExited) if the process has
+  /// already exited. Only available on Unix.
+  #[cfg(unix)]
+  pub fn suspend(&mut self) -> Result<()> {
+    self.signal(libc::SIGSTOP)
+  }
+  /// Continues a server frozen by [suspend](#method.suspend), with SIGCONT. Only available on Unix
+  #[cfg(unix)]
+  pub fn resume(&mut self) -> Result<()> {
+    self.signal(libc::SIGCONT)
+  }
+  #[cfg(unix)]
+  fn signal(&mut self, signal: libc::c_int) -> Result<()> {
+    let pid = self.live_pid()?;
+    //Safe as kill only reads its arguments
+    let result = unsafe { libc::kill(pid as libc::pid_t, signal) };
+    if result == 0 {
+      Ok(())
+    } else {
+      Err(Error::IoError(std::io::Error::last_os_error()))
+    }
+  }
+  /// The server process' pid, checked to still belong to it
+  ///
+  /// Once the process has exited and been reaped by try_wait its pid can be handed to an unrelated
+  /// process. Until then an exited process stays a zombie holding the pid, so checking first is enough
+  fn live_pid(&mut self) -> Result<u32> {
+    match self.try_reap()? {
+      Some(_) => Err(Error::ServerProcessExited()),
+      None => Ok(self.server_process.id()),
+    }
+  }
+  /// Blocks until the server process exits, returning its exit status
+  ///
+  /// # Remarks
+  /// Useful when the server is run in the foreground, or is expected to shut down on its own
+  /// (e.g. via an in-game /stop). The stdin and stdout threads are joined once the process ends.
+  pub fn wait(&mut self) -> Result<ExitStatus> {
+    let status = self.reap()?;
+    self.join_threads();
+    Ok(status)
+  }
+  /// Waits up to `timeout` for the server process to exit, killing it if it doesn't, then joins
+  /// the threads
+  fn wait_or_kill(&mut self, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    while self.try_reap()?.is_none() {
+      if Instant::now() >= deadline {
+        let _ = self.server_process.kill();
+        self.reap()?;
+        self.join_threads();
+        return Err(Error::StopTimeout());
+      }
+      thread::sleep(Duration::from_millis(100));
+    }
+    //The readers only see the end of their pipes once the process is gone, so join afterwards
+    self.join_threads();
+    Ok(())
+  }
+  /// Whether the process is known to have exited, as seen by the stdout thread or a reap
+  fn has_exited(&self) -> bool {
+    self.exit.lock().unwrap().exited
+  }
+  /// Whether the stdout thread saw the process exit on its own after the server finished starting
+  fn crashed(&self) -> bool {
+    self.exit.lock().unwrap().crashed
+  }
+  /// Returns how the server process exited, or None if it is still running
+  pub fn exit_status(&mut self) -> Result<Option<ExitStatus>> {
+    Ok(self.try_reap()?)
+  }
+  /// Reaps the server process if it has exited, recording its status for the stdout thread
+  fn try_reap(&mut self) -> io::Result<Option<ExitStatus>> {
+    let mut exit = self.exit.lock().unwrap();
+    let status = self.server_process.try_wait()?;
+    if let Some(status) = status {
+      exit.record(status);
+    }
+    Ok(status)
+  }
+  /// Waits for the server process to exit and reaps it, like [try_reap](#method.try_reap)
+  fn reap(&mut self) -> io::Result<ExitStatus> {
+    let mut exit = self.exit.lock().unwrap();
+    let status = self.server_process.wait()?;
+    exit.record(status);
+    Ok(status)
+  }
+  /// Signals the stdin and stdout threads to finish, and waits for them
+  fn join_threads(&mut self) {
+    let mut cond = self.thread_cond.write().unwrap();
+    *cond = false;
+    drop(cond);
+    self.stdin_queue.wake();
+    self.schedule = None;
+    let deadline = Instant::now() + READER_JOIN_TIMEOUT;
+    if let Some(handle) = self.stdout_join.take() {
+      join_reader(handle, deadline);
+    }
+    if let Some(handle) = self.stdin_join.take() {
+      let _ = handle.join();
+    }
+    if let Some(handle) = self.stderr_join.take() {
+      join_reader(handle, deadline);
+    }
+  }
+  /// Checks if the server process is still valid (has not crashed or exited).
+  pub fn is_valid(&mut self) -> Result<bool> {
+    match self.try_reap()? {
+      Some(_) => Ok(false),
+      None => Ok(true),
+    }
+  }
+  fn process_check(&mut self) -> Result<()> {
+    match self.is_valid()? {
+      true => Ok(()),
+      false => match *self.failure.read().unwrap() {
+        Some(Failure::Eula) => Err(Error::EulaNotAccepted()),
+        Some(Failure::OutOfMemory) => Err(Error::OutOfMemory()),
+        Some(Failure::PortBind) => Err(Error::PortInUse(self.port)),
+        None => Err(Error::ServerProcessExited()),
+      },
+    }
+  }
+  /// Sends a string to the server stdin
+  /// # Arguments
+  /// * `msg` - A String that contains the message to be sent to the server.
+  ///
+  /// # Remarks
+  /// The message should not contain a trailing newline, as the send method handles it.
+  /// Returns [StdinClosed](enum.Error.html#variant.StdinClosed) once the server has closed its stdin,
+  /// and [InvalidCommand](enum.Error.html#variant.InvalidCommand) without sending anything if the
+  /// message contains a `\n` or `\r`, as each line would run as a separate command.
+  pub fn send(&mut self, msg: String) -> Result<()> {
+    validate_command(&msg)?;
+    self.process_check()?;
+    if *self.stdin_closed.read().unwrap() {
+      return Err(Error::StdinClosed());
+    }
+    self.stdin_queue.push(msg)
+  }
+  /// Runs a command over RCON and returns the server's reply
+  /// # Arguments
+  /// * `cmd` - The command to run, without a leading slash
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// let inst = manager.get("1").unwrap();
+  /// inst.wait_for_ready(std::time::Duration::from_secs(120)).unwrap();
+  /// println!("{}", inst.command("list").unwrap());
+  /// ```
+  /// # Remarks
+  /// Requires `enable-rcon=true` in server.properties, otherwise returns
+  /// [RconDisabled](enum.Error.html#variant.RconDisabled) and [send](#method.send) has to be used
+  /// instead. The server only listens for RCON once it is ready, so this returns
+  /// [ServerStillStarting](enum.Error.html#variant.ServerStillStarting) before then. The connection
+  /// is opened on first use and reused, and is reopened after a failure.
+  pub fn command(&mut self, cmd: &str) -> Result<String> {
+    self.process_check()?;
+    if !self.is_ready() {
+      return Err(Error::ServerStillStarting());
+    }
+    let mut client = match self.rcon.take() {
+      Some(client) => client,
+      None => {
+        let props = PropertiesFile::load(&self.folder.join("server.properties"))?;
+        let (port, password) = rcon_settings(&props)?.ok_or(Error::RconDisabled())?;
+        RconClient::connect(&server_address(&props), port, &password, RCON_TIMEOUT)?
+      }
+    };
+    let reply = client.exec(cmd)?;
+    //Only kept after a successful exchange, a failed connection is reopened on the next command
+    self.rcon = Some(client);
+    Ok(reply)
+  }
+  /// Asks the server for the details the multiplayer screen shows, with a Server List Ping
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use std::time::Duration;
+  ///
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// let inst = manager.get("1").unwrap();
+  /// inst.wait_for_ready(Duration::from_secs(120)).unwrap();
+  /// let ping = inst.ping().unwrap();
+  /// println!("{} ({}/{} players)", ping.motd, ping.players_online, ping.players_max);
+  /// ```
+  /// # Remarks
+  /// Connects to the server's port like a client would, so it works without RCON and shows that
+  /// the server is accepting players, not just that the process is alive. Returns
+  /// [ServerStillStarting](enum.Error.html#variant.ServerStillStarting) if the server isn't
+  /// [ready](#method.is_ready) yet, as it only listens once it is.
+  pub fn ping(&self) -> Result<ServerPing> {
+    if !self.is_ready() {
+      return Err(Error::ServerStillStarting());
+    }
+    let props = PropertiesFile::load(&self.folder.join("server.properties"))?;
+    ping::ping(&server_address(&props), self.port as u16, PING_TIMEOUT)
+  }
+  /// Sends every command in a script file to the server, in order
+  /// # Arguments
+  /// * `path` - A file with one command per line
+  ///
+  /// # Remarks
+  /// Blank lines and lines starting with `#` are skipped. The commands are queued together, so
+  /// no other caller's command can land between them.
+  pub fn run_script(&mut self, path: &Path) -> Result<()> {
+    let script = fs::read_to_string(path)?;
+    let commands: Vec<String> = script
+      .lines()
+      .map(|line| line.trim())
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(String::from)
+      .collect();
+    self.send_all(&commands)
+  }
+  /// Sends several messages to the server stdin, in order and without any other message in between
+  /// # Arguments
+  /// * `msgs` - The messages to send, each without a trailing newline
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let inst = manager.get("1").unwrap();
+  /// inst.send_all(&[
+  ///   String::from("op Steve"),
+  ///   String::from("gamemode creative Steve"),
+  ///   String::from("tp Steve 0 64 0"),
+  /// ]).unwrap();
+  /// ```
+  /// # Remarks
+  /// The messages are queued under a single lock, so commands sent at the same time from other
+  /// threads land before or after the whole batch. If any message contains a line break, none are
+  /// sent and [InvalidCommand](enum.Error.html#variant.InvalidCommand) is returned.
+  pub fn send_all(&mut self, msgs: &[String]) -> Result<()> {
+    for msg in msgs {
+      validate_command(msg)?;
+    }
+    self.process_check()?;
+    if *self.stdin_closed.read().unwrap() {
+      return Err(Error::StdinClosed());
+    }
+    self.stdin_queue.push_all(msgs)
+  }
+  //// Gets the output from server stdout
+  ///  # Arguments
+  ///  * `start` The line number of the first line that should be returned
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// let mut next = 0;
+  /// loop {
+  ///   let inst = manager.get("1").unwrap();
+  ///   for line in inst.get(next) {
+  ///     println!("{}", line);
+  ///   }
+  ///   next = inst.line_count();
+  ///   std::thread::sleep(std::time::Duration::from_secs(1));
+  /// }
+  /// ```
+  /// # Remarks
+  /// The returned Vec will contain the lines in the range of start to the end of output.
+  ///
+  /// Line numbers count every line the server has printed, but only the last
+  /// [console_log_capacity](struct.StartOptions.html#structfield.console_log_capacity) lines are kept.
+  /// A line number keeps referring to the same line after older ones are dropped, so polling with
+  /// the last line number seen stays correct. If `start` is older than
+  /// [first_line](#method.first_line), the Vec begins at the oldest line still kept, which means
+  /// it can be shorter than `line_count() - start`; continue from [line_count](#method.line_count)
+  /// rather than adding the length of the Vec to `start`.
+  pub fn get(&self, start: u32) -> Vec<String> {
+    self.console_log.lock().unwrap().get(start as usize)
+  }
+
+  /// Gets the output from server stdout like [get](#method.get), with each line parsed into a
+  /// [LogLine](struct.LogLine.html)
+  ///  # Arguments
+  ///  * `start` The line number of the first line that should be returned
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let inst = manager.get("1").unwrap();
+  /// for line in inst.get_structured(0) {
+  ///   if line.level == serbo::LogLevel::Error {
+  ///     println!("{}", line.message);
+  ///   }
+  /// }
+  /// ```
+  pub fn get_structured(&self, start: u32) -> Vec<LogLine> {
+    self.get(start).iter().map(|line| LogLine::parse(line)).collect()
+  }
+
+  /// Returns the line number of the oldest line still kept in the console log
+  ///
+  /// # Remarks
+  /// Zero until the console log reaches its capacity, after which it grows by one with each new line.
+  pub fn first_line(&self) -> u32 {
+    self.console_log.lock().unwrap().first_line() as u32
+  }
+
+  /// Returns the number of lines the server has printed to the console log, including dropped ones,
+  /// which is also the line number the next line will get
+  pub fn line_count(&self) -> u32 {
+    self.console_log.lock().unwrap().end() as u32
+  }
+
+  /// Gets the JVM garbage collector output, when it is being kept apart from the console log
+  ///  # Arguments
+  ///  * `start` The line number of the first line that should be returned
+  ///
+  /// # Remarks
+  /// Always empty unless [set_separate_gc_log](struct.Manager.html#method.set_separate_gc_log) was
+  /// enabled before the server started. Lines are numbered and dropped like in [get](#method.get).
+  pub fn gc_log(&self, start: u32) -> Vec<String> {
+    self.gc_log.lock().unwrap().get(start as usize)
+  }
+
+  /// Gets the output from server stderr, such as JVM warnings and crash stack traces
+  ///  # Arguments
+  ///  * `start` The line number of the first line that should be returned
+  ///
+  /// # Remarks
+  /// Lines are numbered and dropped like in [get](#method.get).
+  pub fn get_stderr(&self, start: u32) -> Vec<String> {
+    self.stderr_log.lock().unwrap().get(start as usize)
+  }
+
+  /// Gets the lines of previous runs loaded when the server started, see
+  /// [preload_log_history](struct.StartOptions.html#structfield.preload_log_history)
+  ///  # Arguments
+  ///  * `start` The line number of the first line that should be returned
+  ///
+  /// # Remarks
+  /// The history is numbered on its own, from 0 for its oldest line, and doesn't change while the
+  /// server runs. The lines of the current run are only in [get](#method.get), whose numbering
+  /// starts at 0 with the first line printed this run.
+  pub fn get_history(&self, start: u32) -> Vec<String> {
+    self.history.iter().skip(start as usize).cloned().collect()
+  }
+
+  /// Returns the number of bytes of text held in this server's console, GC and stderr log buffers
+  ///
+  /// # Remarks
+  /// Kept as a running total by the stdout thread, so this is cheap to poll. Only the text of each
+  /// line is counted, not the allocation overhead around it.
+  pub fn log_memory_bytes(&self) -> usize {
+    self.log_bytes.load(Ordering::Relaxed)
+  }
+
+  /// Creates a [Subscription](struct.Subscription.html) that receives every stdout line printed
+  /// from now on
+  ///
+  /// # Remarks
+  /// Lines already in the console log are not replayed; use [get](#method.get) for those.
+  pub fn subscribe(&self) -> Subscription {
+    let (tx, rx) = mpsc::channel();
+    self.subscribers.lock().unwrap().push(tx);
+    Subscription { receiver: rx }
+  }
+  /// Calls `callback` with every stdout line printed from now on
+  /// # Arguments
+  /// * `callback` - Called once per line, in order
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// manager.get("1").unwrap().on_line(|line| println!("[1] {}", line));
+  /// ```
+  /// # Remarks
+  /// The callback runs on a thread of its own, fed by a [Subscription](struct.Subscription.html),
+  /// so a slow callback doesn't hold up the server's output. Like [subscribe](#method.subscribe),
+  /// lines already in the console log are not replayed. The thread ends once the server's output does.
+  pub fn on_line<F>(&self, mut callback: F)
+  where
+    F: FnMut(&str) + Send + 'static,
+  {
+    let subscription = self.subscribe();
+    thread::spawn(move || {
+      while let Some(line) = subscription.next_line() {
+        callback(&line);
+      }
+    });
+  }
+  /// Collects every stdout line the server prints during the next `duration`
+  ///
+  /// # Remarks
+  /// Blocks for the whole duration, unless the server's output ends first. Lines printed before
+  /// the call are not included.
+  pub fn collect_for(&self, duration: Duration) -> Vec<String> {
+    let subscription = self.subscribe();
+    let deadline = Instant::now() + duration;
+    let mut lines = Vec::new();
+    loop {
+      let now = Instant::now();
+      if now >= deadline {
+        break;
+      }
+      match subscription.receiver.recv_timeout(deadline - now) {
+        Ok(line) => lines.push(line),
+        //Timed out, or the server's output ended
+        Err(_) => break,
+      }
+    }
+    lines
+  }
+
   pub fn started(&mut self) {
     self.started = true;
   }
+  /// Returns the memory and CPU currently used by the server process
+  ///
+  /// # Remarks
+  /// The CPU use is averaged since the previous call, so poll this at the interval you want it
+  /// measured over. The first call has nothing to compare against and blocks for a quarter of a
+  /// second to take a second sample. Only the java process itself is measured. Returns
+  /// [Unsupported](enum.Error.html#variant.Unsupported) on platforms other than Linux, and
+  /// [ServerProcessExited](enum.Error.html#variant.ServerProcessExited) once the process has exited.
+  pub fn resource_usage(&self) -> Result<ResourceUsage> {
+    //Holding the lock keeps the process from being reaped, so its pid can't be reused meanwhile
+    let exit = self.exit.lock().unwrap();
+    if exit.exited {
+      return Err(Error::ServerProcessExited());
+    }
+    let pid = self.server_process.id();
+    let mut sample = self.cpu_sample.lock().unwrap();
+    let (mut memory_bytes, mut cpu_time) = resources::process_usage(pid)?;
+    let mut now = Instant::now();
+    let (before, cpu_before) = match sample.take() {
+      Some(previous) => previous,
+      None => {
+        thread::sleep(CPU_SAMPLE_INTERVAL);
+        let first = (now, cpu_time);
+        let usage = resources::process_usage(pid)?;
+        memory_bytes = usage.0;
+        cpu_time = usage.1;
+        now = Instant::now();
+        first
+      }
+    };
+    *sample = Some((now, cpu_time));
+    let elapsed = now.duration_since(before).as_secs_f64();
+    let cpu_percent = if elapsed > 0.0 {
+      ((cpu_time - cpu_before) / elapsed * 100.0) as f32
+    } else {
+      0.0
+    };
+    Ok(ResourceUsage { memory_bytes, cpu_percent })
+  }
+  /// Returns how long ago the server process was launched
+  pub fn uptime(&self) -> Duration {
+    self.spawned.elapsed()
+  }
+  /// Returns the wall clock time at which the server process was launched
+  pub fn started_at(&self) -> SystemTime {
+    self.started_at
+  }
+  /// Returns how many times in a row [recover_crashed](struct.Manager.html#method.recover_crashed)
+  /// has restarted the server after a crash
+  ///
+  /// # Remarks
+  /// Starting the server any other way, including by hand after a stop and through a
+  /// [scheduled](struct.Manager.html#method.schedule) restart, resets the count to 0. A count
+  /// climbing quickly alongside a recent [last_restart](#method.last_restart) means the server is
+  /// crash looping.
+  pub fn restart_count(&self) -> u32 {
+    self.restarts
+  }
+  /// Returns when [recover_crashed](struct.Manager.html#method.recover_crashed) last restarted the
+  /// server, or None if it hasn't since the server was last started any other way
+  pub fn last_restart(&self) -> Option<SystemTime> {
+    match self.restarts {
+      0 => None,
+      _ => Some(self.started_at),
+    }
+  }
+  /// Returns when the server's next [scheduled](struct.Manager.html#method.schedule) restart is,
+  /// or None if it has no restart scheduled or its restart is already due
+  pub fn next_restart(&self) -> Option<SystemTime> {
+    self.schedule.as_ref().and_then(Schedule::next_restart)
+  }
+  /// Returns when the server's next [scheduled](struct.Manager.html#method.schedule) autosave is,
+  /// or None if it has no autosave scheduled
+  pub fn next_autosave(&self) -> Option<SystemTime> {
+    self.schedule.as_ref().and_then(Schedule::next_autosave)
+  }
+  /// Checks whether the server has finished starting
+  ///
+  /// # Remarks
+  /// True once the console printed a line matching the
+  /// [ready](struct.ConsolePatterns.html#structfield.ready) pattern, which by default is the
+  /// `Done (12.345s)! For help, type "help"` line, or once the server was marked ready by hand.
+  pub fn is_ready(&self) -> bool {
+    self.started || !*self.starting.read().unwrap()
+  }
+  /// Checks whether the server is still starting, the opposite of [is_ready](#method.is_ready)
+  pub fn is_starting(&self) -> bool {
+    !self.is_ready()
+  }
+  /// Blocks until the server has finished starting
+  /// # Arguments
+  /// * `timeout` - How long to wait before giving up
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use std::time::Duration;
+  ///
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// manager.start("1", 25565).unwrap();
+  /// let inst = manager.get("1").unwrap();
+  /// inst.wait_for_ready(Duration::from_secs(120)).unwrap();
+  /// inst.send(String::from("say Hello")).unwrap();
+  /// ```
+  /// # Remarks
+  /// Returns [StartTimeout](enum.Error.html#variant.StartTimeout) if the server isn't
+  /// [ready](#method.is_ready) in time. If the process exits while starting, returns
+  /// [ServerProcessExited](enum.Error.html#variant.ServerProcessExited), or the more specific error
+  /// for a failure recognized in its output such as [EulaNotAccepted](enum.Error.html#variant.EulaNotAccepted).
+  pub fn wait_for_ready(&mut self, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+      if self.is_ready() {
+        return Ok(());
+      }
+      self.process_check()?;
+      if Instant::now() >= deadline {
+        return Err(Error::StartTimeout());
+      }
+      thread::sleep(Duration::from_millis(50));
+    }
+  }
+  /// Checks whether the server has started shutting down, according to its console output
+  pub fn is_stopping(&self) -> bool {
+    *self.stopping.read().unwrap()
+  }
+  /// Marks the server as ready, for server software whose readiness line isn't recognized
+  ///
+  /// # Remarks
+  /// Clears the starting state immediately, allowing the server to be stopped.
+  pub fn mark_ready(&self) {
+    let mut starting = self.starting.write().unwrap();
+    *starting = false;
+  }
+  /// Adds a line of Serbo's own to the console log
+  fn push_console_line(&self, line: String) {
+    self.log_bytes.fetch_add(line.len(), Ordering::Relaxed);
+    if let Some(evicted) = self.console_log.lock().unwrap().push(line) {
+      self.log_bytes.fetch_sub(evicted.len(), Ordering::Relaxed);
+    }
+  }
+  /// Sets the difficulty of the running server with the `difficulty` command
+  /// # Arguments
+  /// * `difficulty` - The difficulty to switch to
+  ///
+  /// # Remarks
+  /// The change lasts until the server restarts, use
+  /// [set_difficulty_persistent](#method.set_difficulty_persistent) to keep it. Returns
+  /// [CommandFailed](enum.Error.html#variant.CommandFailed) if the server doesn't confirm it.
+  pub fn set_difficulty(&mut self, difficulty: Difficulty) -> Result<()> {
+    let command = format!("difficulty {}", difficulty.as_str());
+    self.send_and_confirm(command, |line| {
+      line.contains("The difficulty has been set to") || line.contains("The difficulty did not change")
+    })?;
+    Ok(())
+  }
+  /// Changes the game mode of a player, or of every online player, with the `gamemode` command
+  /// # Arguments
+  /// * `gamemode` - The game mode to switch to
+  /// * `target` - The name of an online player, or None for every player (`@a`)
+  ///
+  /// # Remarks
+  /// The server prints nothing for a player already in the game mode, so the command isn't waited
+  /// for. Instead the player is checked against the [online players](#method.players) first,
+  /// returning [PlayerNotOnline](enum.Error.html#variant.PlayerNotOnline) if they aren't there.
+  pub fn set_gamemode(&mut self, gamemode: Gamemode, target: Option<&str>) -> Result<()> {
+    let target = match target {
+      Some(name) => {
+        validate_player_name(name)?;
+        if !self.players()?.iter().any(|player| player.eq_ignore_ascii_case(name)) {
+          return Err(Error::PlayerNotOnline(name.to_string()));
+        }
+        name
+      }
+      None => "@a",
+    };
+    self.send(format!("gamemode {} {}", gamemode.as_str(), target))
+  }
+  /// Sets the time of day in every world with the `time set` command
+  /// # Arguments
+  /// * `time` - The time to switch to
+  ///
+  /// # Remarks
+  /// Returns [CommandFailed](enum.Error.html#variant.CommandFailed) if the server doesn't confirm it.
+  pub fn set_time(&mut self, time: TimeOfDay) -> Result<()> {
+    self.send_and_confirm(format!("time set {}", time.ticks()), |line| line.contains("Set the time to"))?;
+    Ok(())
+  }
+  /// Changes the weather with the `weather` command
+  /// # Arguments
+  /// * `weather` - The weather to switch to
+  ///
+  /// # Remarks
+  /// The server picks how long the weather lasts. Returns
+  /// [CommandFailed](enum.Error.html#variant.CommandFailed) if the server doesn't confirm it.
+  pub fn set_weather(&mut self, weather: Weather) -> Result<()> {
+    self.send_and_confirm(format!("weather {}", weather.as_str()), |line| {
+      //Older versions word it "Changing to clear weather"
+      line.contains("Changing to ") || line.contains("Set the weather to")
+    })?;
+    Ok(())
+  }
+  /// Sets the difficulty of the running server and saves it to server.properties,
+  /// so the change survives a restart
+  /// # Arguments
+  /// * `difficulty` - The difficulty to switch to
+  ///
+  /// # Remarks
+  /// server.properties is only written once [set_difficulty](#method.set_difficulty) has had the
+  /// change confirmed. Returns [CommandFailed](enum.Error.html#variant.CommandFailed) if no
+  /// confirmation arrives.
+  pub fn set_difficulty_persistent(&mut self, difficulty: Difficulty) -> Result<()> {
+    self.set_difficulty(difficulty)?;
+    let path = self.folder.join("server.properties");
+    let mut props = PropertiesFile::load(&path)?;
+    props.set("difficulty", difficulty.as_str());
+    props.save(&path)?;
+    Ok(())
+  }
+  /// Reports the software and Minecraft version of the running server
+  ///
+  /// # Remarks
+  /// The startup banner is checked first. If it doesn't name a version, the `version` command is
+  /// tried, which Bukkit derived servers support. Returns [Unsupported](enum.Error.html#variant.Unsupported)
+  /// if neither works, including when the server is still starting and the command can't be sent
+  /// yet. The result is cached after the first successful detection.
+  pub fn identify(&mut self) -> Result<ServerIdentity> {
+    if let Some(ref identity) = self.identity {
+      return Ok(identity.clone());
+    }
+    let identity = match ServerIdentity::from_banner(&self.get(0)) {
+      Some(identity) => identity,
+      None => {
+        if *self.starting.read().unwrap() {
+          return Err(Error::Unsupported(String::from("identify before the server is ready")));
+        }
+        let response = self.send_and_confirm(String::from("version"), |line| {
+          line.contains("This server is running") || line.contains("Unknown or incomplete command")
+        })?;
+        match ServerIdentity::from_version_command(&response) {
+          Some(identity) => identity,
+          None => return Err(Error::Unsupported(String::from("version command"))),
+        }
+      }
+    };
+    self.identity = Some(identity.clone());
+    Ok(identity)
+  }
+  /// Checks whether a player is currently on the server
+  /// # Arguments
+  /// * `name` - The player's name, matched exactly
+  ///
+  /// # Remarks
+  /// Answered from the join and leave messages seen in the console since the server started,
+  /// without sending any command. Servers that change those messages won't be tracked accurately.
+  pub fn is_player_online(&mut self, name: &str) -> Result<bool> {
+    self.process_check()?;
+    Ok(self.online_players.lock().unwrap().contains(name))
+  }
+  /// Like [is_player_online](#method.is_player_online), but ignores the case of `name`
+  pub fn is_player_online_ignore_case(&mut self, name: &str) -> Result<bool> {
+    self.process_check()?;
+    let players = self.online_players.lock().unwrap();
+    Ok(players.iter().any(|player| player.eq_ignore_ascii_case(name)))
+  }
+  /// Asks the server who is online with the `list` command
+  ///
+  /// # Remarks
+  /// Uses [command](#method.command) when RCON is available, otherwise sends `list` on stdin and
+  /// reads the reply from the console. Servers that change the format of the reply return an
+  /// empty Vec rather than an error. Unlike [is_player_online](#method.is_player_online), this
+  /// asks the server and so is accurate even if join and leave messages were missed.
+  pub fn players(&mut self) -> Result<Vec<String>> {
+    if let Ok(reply) = self.command("list") {
+      return Ok(parse_name_list(&reply, "players online:"));
+    }
+    match self.send_and_confirm(String::from("list"), |line| line.contains("players online")) {
+      Ok(line) => Ok(parse_name_list(&line, "players online:")),
+      Err(Error::CommandFailed(_)) => Ok(Vec::new()),
+      Err(e) => Err(e),
+    }
+  }
+  /// Returns the number of players online, see [players](#method.players)
+  pub fn player_count(&mut self) -> Result<usize> {
+    Ok(self.players()?.len())
+  }
+  /// Adds a player to the whitelist with the `whitelist add` command
+  /// # Arguments
+  /// * `name` - The player's name
+  ///
+  /// # Remarks
+  /// The server looks up the player's UUID, and returns
+  /// [CommandFailed](enum.Error.html#variant.CommandFailed) if no such player exists. Adding a
+  /// player who is already whitelisted succeeds.
+  pub fn whitelist_add(&mut self, name: &str) -> Result<()> {
+    validate_player_name(name)?;
+    let response = self.send_and_confirm(format!("whitelist add {}", name), |line| {
+      line.contains("to the whitelist") || line.contains("already whitelisted") || line.contains("does not exist")
+    })?;
+    if response.contains("does not exist") {
+      return Err(Error::CommandFailed(response));
+    }
+    Ok(())
+  }
+  /// Removes a player from the whitelist with the `whitelist remove` command
+  /// # Arguments
+  /// * `name` - The player's name
+  ///
+  /// # Remarks
+  /// Removing a player who isn't whitelisted succeeds.
+  pub fn whitelist_remove(&mut self, name: &str) -> Result<()> {
+    validate_player_name(name)?;
+    let response = self.send_and_confirm(format!("whitelist remove {}", name), |line| {
+      line.contains("from the whitelist") || line.contains("not whitelisted") || line.contains("does not exist")
+    })?;
+    if response.contains("does not exist") {
+      return Err(Error::CommandFailed(response));
+    }
+    Ok(())
+  }
+  /// Returns the names of the whitelisted players, with the `whitelist list` command
+  pub fn whitelist_list(&mut self) -> Result<Vec<String>> {
+    let response = self.send_and_confirm(String::from("whitelist list"), |line| line.contains("whitelisted player"))?;
+    Ok(parse_name_list(&response, "whitelisted players:"))
+  }
+  /// Makes a player an operator with the `op` command
+  /// # Arguments
+  /// * `name` - The player's name
+  ///
+  /// # Remarks
+  /// Returns [CommandFailed](enum.Error.html#variant.CommandFailed) if no such player exists.
+  /// Opping a player who already is an operator succeeds.
+  pub fn op(&mut self, name: &str) -> Result<()> {
+    validate_player_name(name)?;
+    let response = self.send_and_confirm(format!("op {}", name), |line| {
+      line.contains("a server operator") || line.contains("already is an operator") || line.contains("does not exist")
+    })?;
+    if response.contains("does not exist") {
+      return Err(Error::CommandFailed(response));
+    }
+    Ok(())
+  }
+  /// Takes operator status away from a player with the `deop` command
+  /// # Arguments
+  /// * `name` - The player's name
+  pub fn deop(&mut self, name: &str) -> Result<()> {
+    validate_player_name(name)?;
+    let response = self.send_and_confirm(format!("deop {}", name), |line| {
+      line.contains("no longer a server operator") || line.contains("is not an operator") || line.contains("does not exist")
+    })?;
+    if response.contains("does not exist") {
+      return Err(Error::CommandFailed(response));
+    }
+    Ok(())
+  }
+  /// Returns the names of the server's operators
+  ///
+  /// # Remarks
+  /// There is no command that lists operators, so this reads ops.json, which the server rewrites
+  /// whenever an operator is added or removed.
+  pub fn list_ops(&self) -> Result<Vec<String>> {
+    Ok(PlayerList::load(&self.folder.join("ops.json"))?.names())
+  }
+  /// Disconnects a player with the `kick` command
+  /// # Arguments
+  /// * `name` - The name of an online player
+  /// * `reason` - The message shown to the player, if any
+  ///
+  /// # Remarks
+  /// Returns [PlayerNotOnline](enum.Error.html#variant.PlayerNotOnline) if the player can't be found,
+  /// and [InvalidArgument](enum.Error.html#variant.InvalidArgument) if the reason contains a line break.
+  pub fn kick(&mut self, name: &str, reason: Option<&str>) -> Result<()> {
+    validate_player_name(name)?;
+    let command = with_reason(format!("kick {}", name), reason)?;
+    let response = self.send_and_confirm(command, |line| {
+      line.contains("Kicked ") || line.contains("No player was found")
+    })?;
+    if response.contains("No player was found") {
+      return Err(Error::PlayerNotOnline(name.to_string()));
+    }
+    Ok(())
+  }
+  /// Bans a player with the `ban` command
+  /// # Arguments
+  /// * `name` - The player's name
+  /// * `reason` - The reason recorded with the ban, if any
+  ///
+  /// # Remarks
+  /// The player doesn't need to be online. Returns [CommandFailed](enum.Error.html#variant.CommandFailed)
+  /// if no such player exists. Banning a player who is already banned succeeds.
+  pub fn ban(&mut self, name: &str, reason: Option<&str>) -> Result<()> {
+    validate_player_name(name)?;
+    let command = with_reason(format!("ban {}", name), reason)?;
+    let response = self.send_and_confirm(command, |line| {
+      line.contains("Banned ") || line.contains("already banned") || line.contains("does not exist")
+    })?;
+    if response.contains("does not exist") {
+      return Err(Error::CommandFailed(response));
+    }
+    Ok(())
+  }
+  /// Bans an IP address with the `ban-ip` command
+  /// # Arguments
+  /// * `ip` - An IPv4 or IPv6 address
+  /// * `reason` - The reason recorded with the ban, if any
+  pub fn ban_ip(&mut self, ip: &str, reason: Option<&str>) -> Result<()> {
+    if ip.parse::<IpAddr>().is_err() {
+      return Err(Error::InvalidArgument(format!("{:?} is not a valid IP address", ip)));
+    }
+    let command = with_reason(format!("ban-ip {}", ip), reason)?;
+    let response = self.send_and_confirm(command, |line| {
+      line.contains("Banned IP") || line.contains("already banned") || line.contains("Invalid IP address")
+    })?;
+    if response.contains("Invalid IP address") {
+      return Err(Error::CommandFailed(response));
+    }
+    Ok(())
+  }
+  /// Lifts a player's ban with the `pardon` command
+  /// # Arguments
+  /// * `name` - The player's name
+  ///
+  /// # Remarks
+  /// Pardoning a player who isn't banned succeeds.
+  pub fn pardon(&mut self, name: &str) -> Result<()> {
+    validate_player_name(name)?;
+    self.send_and_confirm(format!("pardon {}", name), |line| {
+      line.contains("Unbanned ") || line.contains("isn't banned")
+    })?;
+    Ok(())
+  }
+  /// Returns the banned players and IP addresses, with the `banlist` command
+  ///
+  /// # Remarks
+  /// The server answers with a `There are N ban(s):` line, `There are N bans:` on some versions,
+  /// followed by one line per ban, which are
+  /// waited for until all of them have been printed.
+  pub fn banlist(&mut self) -> Result<Vec<String>> {
+    let start = self.console_log.lock().unwrap().end();
+    self.send(String::from("banlist"))?;
+    let deadline = Instant::now() + COMMAND_RESPONSE_TIMEOUT;
+    while Instant::now() < deadline {
+      {
+        let log = self.console_log.lock().unwrap();
+        let lines: Vec<&String> = log.lines_from(start).collect();
+        if lines.iter().any(|line| line.contains("There are no bans")) {
+          return Ok(Vec::new());
+        }
+        let header = lines
+          .iter()
+          .enumerate()
+          .find_map(|(index, line)| parse_ban_count(line).map(|count| (index, count)));
+        if let Some((header, count)) = header {
+          let bans: Vec<String> = lines[header + 1..]
+            .iter()
+            .filter_map(|line| parse_ban_entry(line))
+            .take(count)
+            .collect();
+          if bans.len() == count {
+            return Ok(bans);
+          }
+        }
+      }
+      self.process_check()?;
+      thread::sleep(Duration::from_millis(50));
+    }
+    Err(Error::CommandFailed(String::from("no response to `banlist`")))
+  }
+  /// Teleports a player to the given coordinates
+  /// # Arguments
+  /// * `player` - The name of an online player
+  /// * `x`, `y`, `z` - The coordinates to teleport to
+  ///
+  /// # Remarks
+  /// Returns [PlayerNotOnline](enum.Error.html#variant.PlayerNotOnline) if the server can't find the player.
+  pub fn teleport(&mut self, player: &str, x: f64, y: f64, z: f64) -> Result<()> {
+    let position = [coordinate(x, false)?, coordinate(y, false)?, coordinate(z, false)?];
+    self.teleport_player(player, &position)
+  }
+  /// Teleports a player by an offset from their current position, like `~dx ~dy ~dz`
+  /// # Arguments
+  /// * `player` - The name of an online player
+  /// * `dx`, `dy`, `dz` - How far to move the player along each axis
+  pub fn teleport_relative(&mut self, player: &str, dx: f64, dy: f64, dz: f64) -> Result<()> {
+    let position = [coordinate(dx, true)?, coordinate(dy, true)?, coordinate(dz, true)?];
+    self.teleport_player(player, &position)
+  }
+  fn teleport_player(&mut self, player: &str, position: &[String; 3]) -> Result<()> {
+    validate_player_name(player)?;
+    let command = format!("tp {} {} {} {}", player, position[0], position[1], position[2]);
+    let response = self.send_and_confirm(command, |line| {
+      line.contains("Teleported ") || line.contains("No entity was found")
+    })?;
+    if response.contains("No entity was found") {
+      return Err(Error::PlayerNotOnline(player.to_string()));
+    }
+    Ok(())
+  }
+  /// Gives a player some of an item
+  /// # Arguments
+  /// * `player` - The name of an online player
+  /// * `item` - The item id, e.g. `minecraft:diamond` or `diamond`
+  /// * `count` - How many to give, from 1 to 6400
+  ///
+  /// # Examples
+  /// ```no_run
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let inst = manager.get("1").unwrap();
+  /// //e.g. "Gave 64 [Diamond] to Steve"
+  /// println!("{}", inst.give("Steve", "minecraft:diamond", 64).unwrap());
+  /// ```
+  /// # Remarks
+  /// Returns the server's confirmation without the log prefix. Returns
+  /// [InvalidArgument](enum.Error.html#variant.InvalidArgument) without sending anything if the
+  /// player name, item id or count isn't valid, [UnknownItem](enum.Error.html#variant.UnknownItem)
+  /// if the server doesn't know the item, and [PlayerNotOnline](enum.Error.html#variant.PlayerNotOnline)
+  /// if the player can't be found.
+  pub fn give(&mut self, player: &str, item: &str, count: u32) -> Result<String> {
+    validate_player_name(player)?;
+    validate_item_id(item)?;
+    validate_give_count(count)?;
+    let command = format!("give {} {} {}", player, item, count);
+    let response = self.send_and_confirm(command, |line| {
+      line.contains("Gave ") || line.contains("Unknown item") || line.contains("No player was found")
+    })?;
+    if response.contains("Unknown item") {
+      return Err(Error::UnknownItem(item.to_string()));
+    }
+    if response.contains("No player was found") {
+      return Err(Error::PlayerNotOnline(player.to_string()));
+    }
+    Ok(LogLine::parse(&response).message)
+  }
+  /// Checks whether the server answers a `list` command on the console
+  fn responds_to_console(&mut self) -> bool {
+    self
+      .send_and_confirm(String::from("list"), |line| line.contains("players online"))
+      .is_ok()
+  }
+  /// Sends a command and waits for a console line accepted by `confirmed`, returning that line
+  fn send_and_confirm<F>(&mut self, command: String, confirmed: F) -> Result<String>
+  where
+    F: Fn(&str) -> bool,
+  {
+    self.send_and_capture(&command, confirmed, COMMAND_RESPONSE_TIMEOUT)
+  }
+  /// Sends a command and returns the first console line after it that `matcher` accepts
+  /// # Arguments
+  /// * `cmd` - The command to send, as for [send](#method.send)
+  /// * `matcher` - Picks out the line that answers the command
+  /// * `timeout` - How long to wait for a matching line
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use std::time::Duration;
+  ///
+  /// let mut manager = serbo::Manager::new("servers", "versions");
+  /// let inst = manager.get("1").unwrap();
+  /// let line = inst.send_and_capture("seed", |line| line.contains("Seed: "), Duration::from_secs(5)).unwrap();
+  /// println!("{}", line);
+  /// ```
+  /// # Remarks
+  /// A lightweight alternative to [command](#method.command) for servers without RCON, but only
+  /// best effort: the console doesn't say which command a line answers, so under heavy concurrent
+  /// command traffic the line can belong to another caller's command.
+  ///
+  /// Returns [CommandFailed](enum.Error.html#variant.CommandFailed) once `timeout` passes without
+  /// a matching line, saying which command went unanswered.
+  /// [StartTimeout](enum.Error.html#variant.StartTimeout) isn't used, as it means the server never
+  /// became ready. If the server exits while waiting,
+  /// [ServerProcessExited](enum.Error.html#variant.ServerProcessExited) is returned instead.
+  pub fn send_and_capture<F>(&mut self, cmd: &str, matcher: F, timeout: Duration) -> Result<String>
+  where
+    F: Fn(&str) -> bool,
+  {
+    let start = self.console_log.lock().unwrap().end();
+    self.send(cmd.to_string())?;
+    let deadline = Instant::now() + timeout;
+    let mut checked = start;
+    while Instant::now() < deadline {
+      {
+        let log = self.console_log.lock().unwrap();
+        if let Some(line) = log.lines_from(checked).find(|line| matcher(line)) {
+          return Ok(line.clone());
+        }
+        checked = log.end();
+      }
+      self.process_check()?;
+      thread::sleep(Duration::from_millis(50));
+    }
+    Err(Error::CommandFailed(format!("no response to `{}`", cmd)))
+  }
+}
+
+impl Drop for Instance {
+  fn drop(&mut self) {
+    if let Ok(None) = self.try_reap() {
+      let _ = self.stop();
+      let _ = self.wait_or_kill(self.options.stop_timeout.unwrap_or(DROP_STOP_TIMEOUT));
+    } else {
+      self.join_threads();
+    }
+  }
+}
+
+/// A per-consumer stream of new stdout lines from a server.
+/// Created by calling [subscribe](struct.Instance.html#method.subscribe) on an [Instance](struct.Instance.html)
+///
+/// Each subscription keeps its own position, so several consumers can read the same output
+/// independently. Dropping a subscription unregisters it.
+#[derive(Debug)]
+pub struct Subscription {
+  receiver: Receiver<String>,
+}
+
+impl Subscription {
+  /// Returns the next line if one is available, without blocking
+  pub fn try_next_line(&self) -> Option<String> {
+    self.receiver.try_recv().ok()
+  }
+  /// Blocks until the next line arrives. Returns None once the server's output has ended
+  pub fn next_line(&self) -> Option<String> {
+    self.receiver.recv().ok()
+  }
+  /// Blocks until the next line arrives or `timeout` elapses
+  pub fn next_line_timeout(&self, timeout: Duration) -> Option<String> {
+    self.receiver.recv_timeout(timeout).ok()
+  }
+}
+
+/// The software and Minecraft version of a server, as reported by the running process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerIdentity {
+  /// The server software, e.g. Vanilla, Fabric, Forge or Paper
+  pub software: String,
+  /// The Minecraft version, e.g. 1.16.1
+  pub version: String,
+}
+
+impl ServerIdentity {
+  /// Finds the software and version in the lines printed while the server boots
+  fn from_banner(lines: &[String]) -> Option<ServerIdentity> {
+    let version = lines.iter().find_map(|line| {
+      let index = line.find("Starting minecraft server version ")?;
+      let rest = &line[index + "Starting minecraft server version ".len()..];
+      Some(rest.trim().to_string())
+    })?;
+    let software = if lines.iter().any(|l| l.contains("[FabricLoader]") || l.contains("Fabric Loader")) {
+      "Fabric"
+    } else if lines.iter().any(|l| l.contains("MinecraftForge") || l.contains("Forge Mod Loader")) {
+      "Forge"
+    } else if lines.iter().any(|l| l.contains("Paper version")) {
+      "Paper"
+    } else if lines.iter().any(|l| l.contains("CraftBukkit version")) {
+      "Spigot"
+    } else {
+      "Vanilla"
+    };
+    Some(ServerIdentity {
+      software: software.to_string(),
+      version,
+    })
+  }
+  /// Parses a response such as `This server is running Paper version git-Paper-397 (MC: 1.16.1) ...`
+  fn from_version_command(line: &str) -> Option<ServerIdentity> {
+    let index = line.find("This server is running ")?;
+    let rest = &line[index + "This server is running ".len()..];
+    let software = rest.split(" version").next()?.trim();
+    let mc_index = rest.find("(MC: ")?;
+    let version = rest[mc_index + "(MC: ".len()..].split(')').next()?.trim();
+    Some(ServerIdentity {
+      software: software.to_string(),
+      version: version.to_string(),
+    })
+  }
+}
+
+/// The lifecycle state of a server, as reported by [Manager::status](struct.Manager.html#method.status)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerStatus {
+  /// Not running, including servers whose process has exited or crashed
+  Offline,
+  /// Launched, but not ready yet
+  Starting,
+  /// Ready and accepting commands
+  Running,
+  /// Shutting down
+  Stopping,
+}
+
+/// The difficulty levels of a Minecraft world
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+  Peaceful,
+  Easy,
+  Normal,
+  Hard,
+}
+
+impl Difficulty {
+  /// The name used for this difficulty in commands and server.properties
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      Difficulty::Peaceful => "peaceful",
+      Difficulty::Easy => "easy",
+      Difficulty::Normal => "normal",
+      Difficulty::Hard => "hard",
+    }
+  }
+}
+
+impl FromStr for Difficulty {
+  type Err = Error;
+  /// Parses a difficulty name, or the numeric ids 0 to 3 used by older server.properties files
+  fn from_str(s: &str) -> Result<Difficulty> {
+    match s.to_ascii_lowercase().as_str() {
+      "peaceful" | "0" => Ok(Difficulty::Peaceful),
+      "easy" | "1" => Ok(Difficulty::Easy),
+      "normal" | "2" => Ok(Difficulty::Normal),
+      "hard" | "3" => Ok(Difficulty::Hard),
+      _ => Err(Error::InvalidArgument(format!("{:?} is not a difficulty", s))),
+    }
+  }
+}
+
+/// The game modes a player can be in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gamemode {
+  Survival,
+  Creative,
+  Adventure,
+  Spectator,
+}
+
+impl Gamemode {
+  /// The name used for this game mode in commands and server.properties
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      Gamemode::Survival => "survival",
+      Gamemode::Creative => "creative",
+      Gamemode::Adventure => "adventure",
+      Gamemode::Spectator => "spectator",
+    }
+  }
+}
+
+impl FromStr for Gamemode {
+  type Err = Error;
+  /// Parses a game mode name, or the numeric ids 0 to 3 used by older server.properties files
+  fn from_str(s: &str) -> Result<Gamemode> {
+    match s.to_ascii_lowercase().as_str() {
+      "survival" | "0" => Ok(Gamemode::Survival),
+      "creative" | "1" => Ok(Gamemode::Creative),
+      "adventure" | "2" => Ok(Gamemode::Adventure),
+      "spectator" | "3" => Ok(Gamemode::Spectator),
+      _ => Err(Error::InvalidArgument(format!("{:?} is not a game mode", s))),
+    }
+  }
+}
+
+/// A time of day for [set_time](struct.Instance.html#method.set_time)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOfDay {
+  /// Morning, 1000 ticks
+  Day,
+  /// 6000 ticks
+  Noon,
+  /// Dusk, 13000 ticks
+  Night,
+  /// 18000 ticks
+  Midnight,
+  /// A time in ticks, where a day is 24000 ticks starting at sunrise
+  Ticks(u32),
+}
+
+impl TimeOfDay {
+  /// The time in ticks, as used by the `time set` command
+  pub fn ticks(&self) -> u32 {
+    match *self {
+      TimeOfDay::Day => 1000,
+      TimeOfDay::Noon => 6000,
+      TimeOfDay::Night => 13000,
+      TimeOfDay::Midnight => 18000,
+      TimeOfDay::Ticks(ticks) => ticks,
+    }
+  }
+}
+
+/// The kinds of weather for [set_weather](struct.Instance.html#method.set_weather)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+  Clear,
+  Rain,
+  Thunder,
+}
+
+impl Weather {
+  /// The name used for this weather in the `weather` command
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      Weather::Clear => "clear",
+      Weather::Rain => "rain",
+      Weather::Thunder => "thunder",
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn server_ids_must_be_plain_folder_names() {
+    for id in &["", ".", "..", "../other", "/etc", "a/b", "a\\b", "a\nb", "a\0b"] {
+      assert!(validate_server_id(id).is_err(), "{:?} was accepted", id);
+    }
+    for id in &["1", "survival", "my.server", "..hidden"] {
+      assert!(validate_server_id(id).is_ok(), "{:?} was refused", id);
+    }
+  }
+
+  #[test]
+  fn commands_must_be_a_single_line() {
+    for msg in &["say hi\nop me", "a\rb", "list\n"] {
+      assert!(matches!(validate_command(msg), Err(Error::InvalidCommand(_))), "{:?} was accepted", msg);
+    }
+    assert!(validate_command("say hi, op me").is_ok());
+  }
+
+  #[test]
+  fn delete_refuses_ids_outside_the_server_folder() {
+    let base = std::env::temp_dir().join(format!("serbo-delete-{}", std::process::id()));
+    let servers = base.join("servers");
+    fs::create_dir_all(&servers).unwrap();
+    fs::write(base.join("keep.txt"), "kept").unwrap();
+    let mut manager = Manager::new(servers.to_str().unwrap(), base.join("versions").to_str().unwrap());
+    for id in &["", ".", ".."] {
+      assert!(matches!(manager.delete(id), Err(Error::InvalidArgument(_))));
+    }
+    assert!(!manager.exists(".."));
+    assert!(servers.is_dir() && base.join("keep.txt").is_file());
+    fs::remove_dir_all(&base).unwrap();
+  }
+
+  #[test]
+  fn each_server_has_its_own_world_container() {
+    let mut manager = Manager::new("servers", "versions");
+    assert_eq!(manager.world_folder("1").unwrap(), Path::new("servers").join("1"));
+    manager.set_world_container(Some(PathBuf::from("worlds")));
+    assert_eq!(manager.world_folder("1").unwrap(), Path::new("worlds").join("1"));
+    assert_eq!(manager.world_folder("2").unwrap(), Path::new("worlds").join("2"));
+    assert!(manager.world_folder("..").is_err());
+  }
+
+  #[test]
+  fn server_identity_is_read_from_the_banner() {
+    let fabric: Vec<String> = [
+      "[15:36:51] [main/INFO]: Loading for game Minecraft 1.16.1",
+      "[15:36:52] [main/INFO]: [FabricLoader] Loading 5 mods: minecraft@1.16.1, lithium@0.5.1, fabricloader@0.9.0+build.204, carpet@1.4.0, phosphor@0.6.0+build.7",
+      "[15:37:18] [Worker-Main-5/INFO]: Loaded 927 advancements",
+      "[15:37:19] [Server thread/INFO]: Starting minecraft server version 1.16.1",
+      "[15:37:19] [Server thread/INFO]: Loading properties",
+    ]
+    .iter()
+    .map(|line| line.to_string())
+    .collect();
+    let identity = ServerIdentity::from_banner(&fabric).unwrap();
+    assert_eq!(identity.software, "Fabric");
+    assert_eq!(identity.version, "1.16.1");
+    let vanilla = vec![String::from("[15:37:19] [Server thread/INFO]: Starting minecraft server version 1.16.1")];
+    assert_eq!(ServerIdentity::from_banner(&vanilla).unwrap().software, "Vanilla");
+    assert_eq!(ServerIdentity::from_banner(&fabric[..3]), None);
+    let paper = "[12:00:00 INFO]: This server is running Paper version git-Paper-397 (MC: 1.16.1) (Implementing API version 1.16.1-R0.1-SNAPSHOT)";
+    let identity = ServerIdentity::from_version_command(paper).unwrap();
+    assert_eq!(identity.software, "Paper");
+    assert_eq!(identity.version, "1.16.1");
+    let unknown = "[12:00:00] [Server thread/INFO]: Unknown or incomplete command, see below for error";
+    assert_eq!(ServerIdentity::from_version_command(unknown), None);
+  }
+
+  #[test]
+  fn coordinates_are_formatted_for_commands() {
+    assert_eq!(coordinate(100.0, false).unwrap(), "100.0");
+    assert_eq!(coordinate(-64.0, false).unwrap(), "-64.0");
+    assert_eq!(coordinate(0.5, false).unwrap(), "0.5");
+    assert_eq!(coordinate(-12.25, false).unwrap(), "-12.25");
+    assert_eq!(coordinate(0.0, true).unwrap(), "~");
+    assert_eq!(coordinate(3.0, true).unwrap(), "~3");
+    assert_eq!(coordinate(-1.5, true).unwrap(), "~-1.5");
+    assert!(coordinate(f64::NAN, false).is_err());
+    assert!(coordinate(f64::INFINITY, true).is_err());
+  }
+
+  #[test]
+  fn eula_is_read_like_a_properties_file() {
+    let base = std::env::temp_dir().join(format!("serbo-eula-{}", std::process::id()));
+    let folder = base.join("servers").join("1");
+    fs::create_dir_all(&folder).unwrap();
+    fs::write(folder.join("server.jar"), "").unwrap();
+    assert!(!eula_accepted(&folder));
+    for accepted in &["eula=true", "#By changing the setting below to TRUE\neula = true", "eula:TRUE", "eula true"] {
+      fs::write(folder.join("eula.txt"), accepted).unwrap();
+      assert!(eula_accepted(&folder), "{:?}", accepted);
+    }
+    for refused in &["eula=false", "#eula=true", "eula=truee", "eula="] {
+      fs::write(folder.join("eula.txt"), refused).unwrap();
+      assert!(!eula_accepted(&folder), "{:?}", refused);
+    }
+    let mut manager = Manager::new(base.join("servers").to_str().unwrap(), "versions");
+    let options = StartOptions {
+      java_path: base.join("no-such-java"),
+      check_port: false,
+      ..Default::default()
+    };
+    match manager.start_with("1", 0, &options) {
+      Err(Error::EulaNotAccepted()) => {}
+      other => panic!("expected EulaNotAccepted, got {:?}", other),
+    }
+    assert_eq!(fs::read_to_string(folder.join("eula.txt")).unwrap(), "eula=");
+    let _ = fs::remove_dir_all(&base);
+  }
+
+  #[test]
+  fn give_arguments_are_validated() {
+    for player in &["Steve", "Alex_2", "a", "ABCDEFGHIJKLMNOP"] {
+      assert!(validate_player_name(player).is_ok(), "{}", player);
+    }
+    for player in &["", "ABCDEFGHIJKLMNOPQ", "Steve Alex", "@a", "Steve\nop"] {
+      assert!(validate_player_name(player).is_err(), "{}", player);
+    }
+    for item in &["diamond", "minecraft:diamond", "create:brass_ingot", "tconstruct:part/pick_head"] {
+      assert!(validate_item_id(item).is_ok(), "{}", item);
+    }
+    for item in &["", ":diamond", "minecraft:", "Diamond", "diamond 64", "diamond{Damage:1}", "a:b:c"] {
+      assert!(validate_item_id(item).is_err(), "{}", item);
+    }
+    assert!(validate_give_count(1).is_ok());
+    assert!(validate_give_count(MAX_GIVE_COUNT).is_ok());
+    assert!(validate_give_count(0).is_err());
+    assert!(validate_give_count(MAX_GIVE_COUNT + 1).is_err());
+  }
+
+  #[test]
+  fn player_lines_are_parsed() {
+    let joined = player_event("[15:46:13] [Server thread/INFO]: sprdsprd joined the game");
+    assert_eq!(joined, Some((String::from("sprdsprd"), true)));
+    let left = player_event("[15:51:41] [Server thread/INFO]: sprdsprd left the game");
+    assert_eq!(left, Some((String::from("sprdsprd"), false)));
+    assert_eq!(player_event("[15:47:02] [Server thread/INFO]: <sprdsprd> I joined the game"), None);
+    assert_eq!(player_event("[15:46:13] [Server thread/INFO]: sprdsprd lost connection: Disconnected"), None);
+    assert_eq!(player_event("sprdsprd joined the game"), None);
+    let list = "[15:48:00] [Server thread/INFO]: There are 2 of a max of 20 players online: sprdsprd, Steve";
+    assert_eq!(parse_name_list(list, "players online:"), vec!["sprdsprd", "Steve"]);
+    let nobody = "[15:48:00] [Server thread/INFO]: There are 0 of a max of 20 players online: ";
+    assert!(parse_name_list(nobody, "players online:").is_empty());
+    assert!(parse_name_list("[15:48:00] [Server thread/INFO]: Online: sprdsprd", "players online:").is_empty());
+    let whitelist = "[15:48:00] [Server thread/INFO]: There are 2 whitelisted players: Alex, Steve";
+    assert_eq!(parse_name_list(whitelist, "whitelisted players:"), vec!["Alex", "Steve"]);
+  }
+
+  #[test]
+  fn banlist_replies_are_parsed() {
+    assert_eq!(parse_ban_count("[12:00:00] [Server thread/INFO]: There are 2 ban(s):"), Some(2));
+    assert_eq!(parse_ban_count("[12:00:00] [Server thread/INFO]: There are 1 bans:"), Some(1));
+    assert_eq!(parse_ban_count("[12:00:00] [Server thread/INFO]: There are no bans"), None);
+    assert_eq!(parse_ban_count("[12:00:00] [Server thread/INFO]: There are 0 of a max of 20 players online:"), None);
+    let entry = "[12:00:00] [Server thread/INFO]: Steve was banned by Server: Banned by an operator.";
+    assert_eq!(parse_ban_entry(entry), Some(String::from("Steve")));
+    let ip = "[12:00:00] [Server thread/INFO]: 192.168.0.7 was banned by Alex: griefing";
+    assert_eq!(parse_ban_entry(ip), Some(String::from("192.168.0.7")));
+    assert_eq!(parse_ban_entry("[12:00:00] [Server thread/INFO]: Steve joined the game"), None);
+  }
+
+  #[test]
+  fn create_writes_the_properties_that_are_set_and_imports_worlds() {
+    let base = std::env::temp_dir().join(format!("serbo-create-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("versions").join("1.16.1")).unwrap();
+    fs::write(base.join("versions").join("1.16.1").join("server.jar"), "").unwrap();
+    fs::write(base.join("versions").join("1.16.1").join("server.properties"), "allow-flight=false\nallow-nether=true\n").unwrap();
+    let mut manager = Manager::new(base.join("servers").to_str().unwrap(), base.join("versions").to_str().unwrap());
+    let mut options = CreateOptions::new("1.16.1");
+    options.allow_flight = Some(true);
+    options.force_gamemode = Some(true);
+    manager.create("1", &options).unwrap();
+    let props = manager.load_properties("1").unwrap();
+    assert_eq!(props.get("allow-flight"), Some("true"));
+    assert_eq!(props.get("force-gamemode"), Some("true"));
+    assert_eq!(props.get("allow-nether"), Some("true"));
+    assert!(manager.create("1", &options).is_err());
+    assert!(manager.create("2", &CreateOptions::new("1.12")).is_err());
+    let mut options = CreateOptions::new("1.16.1");
+    options.world_import = Some(base.join("versions").join("1.16.1"));
+    assert!(manager.create("2", &options).is_err());
+    assert!(!base.join("servers").join("2").exists());
+    let save = base.join("saves").join("New World");
+    fs::create_dir_all(save.join("region")).unwrap();
+    fs::write(save.join("level.dat"), "level").unwrap();
+    options.world_import = Some(save);
+    manager.create("2", &options).unwrap();
+    let world = base.join("servers").join("2").join("world");
+    assert_eq!(fs::read_to_string(world.join("level.dat")).unwrap(), "level");
+    assert!(world.join("region").is_dir());
+    let _ = fs::remove_dir_all(&base);
+  }
+
+  #[cfg(unix)]
+  mod fake_server {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Echoes what it reads from stdin into stdin.txt, the way a server's console would take it
+    const SCRIPT: &str = r#"#!/bin/sh
+echo '[12:00:00] [Server thread/INFO]: Done (1.0s)! For help, type "help"'
+while read line; do
+  echo "$line" >> stdin.txt
+  [ "$line" = /stop ] && exit 0
+done
+echo '<end of stdin>' >> stdin.txt
+"#;
+
+    //A script being written while another test forks can't be executed, so run them one by one
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// Waits for the stdout thread to report a crash, asserting it comes
+    fn wait_for_crash(output: &Subscription) {
+      let deadline = Instant::now() + Duration::from_secs(10);
+      while let Some(line) = output.next_line_timeout(deadline.saturating_duration_since(Instant::now())) {
+        if line.starts_with("[Serbo] Server crashed") {
+          return;
+        }
+      }
+      panic!("no crash was reported");
+    }
+
+    /// A Manager with one server, `1`, whose java is `script`
+    fn fake_server(name: &str, script: &str) -> (Manager, StartOptions, PathBuf) {
+      let base = std::env::temp_dir().join(format!("serbo-{}-{}", name, std::process::id()));
+      let folder = base.join("servers").join("1");
+      fs::create_dir_all(&folder).unwrap();
+      fs::write(folder.join("server.jar"), "").unwrap();
+      let java = base.join("java");
+      fs::write(&java, script).unwrap();
+      fs::set_permissions(&java, fs::Permissions::from_mode(0o755)).unwrap();
+      let manager = Manager::new(base.join("servers").to_str().unwrap(), base.join("versions").to_str().unwrap());
+      let options = StartOptions {
+        java_path: java,
+        accept_eula: true,
+        check_port: false,
+        ..Default::default()
+      };
+      (manager, options, base)
+    }
+
+    #[test]
+    fn nothing_is_said_on_start() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let (mut manager, options, base) = fake_server("no-say", SCRIPT);
+      manager.start_with("1", 0, &options).unwrap();
+      manager.get("1").unwrap().wait_for_ready(Duration::from_secs(10)).unwrap();
+      thread::sleep(Duration::from_millis(200));
+      manager.stop("1").unwrap();
+      let stdin = fs::read_to_string(base.join("servers").join("1").join("stdin.txt")).unwrap();
+      assert_eq!(stdin, "/stop\n");
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn idle_server_keeps_its_stdin_open() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let (mut manager, options, base) = fake_server("idle-stdin", SCRIPT);
+      manager.start_with("1", 0, &options).unwrap();
+      manager.get("1").unwrap().wait_for_ready(Duration::from_secs(10)).unwrap();
+      //With nothing to write, the writer thread must still hold the pipe open
+      thread::sleep(Duration::from_secs(1));
+      assert!(manager.is_online("1"));
+      assert!(!base.join("servers").join("1").join("stdin.txt").exists());
+      manager.stop("1").unwrap();
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn stop_all_stops_servers_that_are_still_starting() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      //Never reports being done, but runs /stop once it gets to it
+      let loading = SCRIPT.replacen("echo '[12:00:00] [Server thread/INFO]: Done", "true '", 1);
+      let (mut manager, options, base) = fake_server("stop-all-starting", &loading);
+      manager.set_stop_timeout(Duration::from_secs(5));
+      manager.start_with("1", 0, &options).unwrap();
+      let results = manager.stop_all();
+      assert!(matches!(results[..], [(ref id, Ok(()))] if id == "1"), "{:?}", results);
+      assert_eq!(manager.last_exit_status("1").and_then(|status| status.code()), Some(0));
+      fs::remove_dir_all(&base).unwrap();
+      //Never reads its stdin at all, so it has to be killed
+      let (mut manager, options, base) = fake_server("stop-all-stuck", "#!/bin/sh\nexec sleep 30\n");
+      manager.set_stop_timeout(Duration::from_millis(500));
+      manager.start_with("1", 0, &options).unwrap();
+      let results = manager.stop_all();
+      assert!(matches!(results[..], [(_, Err(Error::StopTimeout()))]), "{:?}", results);
+      assert!(!manager.is_online("1"));
+      assert_eq!(manager.last_exit_status("1").and_then(|status| status.code()), None);
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn restarts_after_crashes_are_counted_until_a_manual_start() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      //Crashes shortly after its first start, then runs normally
+      let crashing = SCRIPT.replacen("while", "[ -f crashed ] || { touch crashed; sleep 0.5; exit 1; }\nwhile", 1);
+      let (mut manager, mut options, base) = fake_server("restart-count", &crashing);
+      options.auto_restart = true;
+      manager.start_with("1", 0, &options).unwrap();
+      let inst = manager.get("1").unwrap();
+      let output = inst.subscribe();
+      inst.wait_for_ready(Duration::from_secs(10)).unwrap();
+      assert_eq!((inst.restart_count(), inst.last_restart()), (0, None));
+      wait_for_crash(&output);
+      assert_eq!(manager.recover_crashed().len(), 1);
+      let inst = manager.get("1").unwrap();
+      assert_eq!(inst.restart_count(), 1);
+      assert_eq!(inst.last_restart(), Some(inst.started_at()));
+      inst.wait_for_ready(Duration::from_secs(10)).unwrap();
+      manager.stop("1").unwrap();
+      manager.start_with("1", 0, &options).unwrap();
+      let inst = manager.get("1").unwrap();
+      assert_eq!(inst.restart_count(), 0);
+      inst.wait_for_ready(Duration::from_secs(10)).unwrap();
+      manager.stop("1").unwrap();
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn subscribe_all_follows_servers_started_later() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let (mut manager, options, base) = fake_server("subscribe-all", SCRIPT);
+      let second = base.join("servers").join("2");
+      fs::create_dir_all(&second).unwrap();
+      fs::write(second.join("server.jar"), "").unwrap();
+      manager.start_with("1", 0, &options).unwrap();
+      manager.get("1").unwrap().wait_for_ready(Duration::from_secs(10)).unwrap();
+      let lines = manager.subscribe_all();
+      manager.start_with("2", 0, &options).unwrap();
+      manager.get("2").unwrap().wait_for_ready(Duration::from_secs(10)).unwrap();
+      let (id, line) = lines.recv_timeout(Duration::from_secs(5)).unwrap();
+      assert_eq!(id, "2");
+      assert!(line.contains("Done"), "{}", line);
+      manager.stop("2").unwrap();
+      manager.stop("1").unwrap();
+      assert!(lines.try_recv().is_err());
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn history_is_preloaded_apart_from_the_current_run() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let (mut manager, mut options, base) = fake_server("preload-history", SCRIPT);
+      options.persist_console_log = true;
+      manager.start_with("1", 0, &options).unwrap();
+      let inst = manager.get("1").unwrap();
+      inst.wait_for_ready(Duration::from_secs(10)).unwrap();
+      let first_run = inst.get(0);
+      assert!(inst.get_history(0).is_empty());
+      manager.stop("1").unwrap();
+      options.preload_log_history = 100;
+      manager.start_with("1", 0, &options).unwrap();
+      let inst = manager.get("1").unwrap();
+      assert_eq!(inst.get_history(0), first_run);
+      assert_eq!(inst.get_history(1), first_run[1..].to_vec());
+      inst.wait_for_ready(Duration::from_secs(10)).unwrap();
+      assert_eq!(inst.get(0).len(), first_run.len());
+      manager.stop("1").unwrap();
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn crashes_are_reported_and_restarted_up_to_the_cap() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let crashing = SCRIPT.replacen("while", "sleep 0.3\nexit 3\nwhile", 1);
+      let (mut manager, mut options, base) = fake_server("restart-cap", &crashing);
+      options.auto_restart = true;
+      options.max_restarts = 1;
+      manager.start_with("1", 0, &options).unwrap();
+      wait_for_crash(&manager.get("1").unwrap().subscribe());
+      //Recorded by the stdout thread, before anything reaps the process
+      assert_eq!(manager.last_exit_status("1").and_then(|status| status.code()), Some(3));
+      assert!(manager.list_online().is_empty());
+      let crashes = manager.recover_crashed();
+      assert!(matches!(crashes[..], [Crash { restart: Some(Ok(_)), .. }]), "{:?}", crashes);
+      wait_for_crash(&manager.get("1").unwrap().subscribe());
+      let crashes = manager.recover_crashed();
+      assert!(matches!(crashes[..], [Crash { restart: None, .. }]), "{:?}", crashes);
+      assert_eq!(manager.status("1"), ServerStatus::Offline);
+      assert!(manager.recover_crashed().is_empty());
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn running_lists_online_servers_with_their_ports() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let (mut manager, options, base) = fake_server("running", SCRIPT);
+      let port = manager.start_with("1", 0, &options).unwrap();
+      assert_eq!(manager.running(), vec![(String::from("1"), port)]);
+      manager.get("1").unwrap().wait_for_ready(Duration::from_secs(10)).unwrap();
+      manager.stop("1").unwrap();
+      assert!(manager.running().is_empty());
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn each_server_is_killed_after_its_own_stop_timeout() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let script = "#!/bin/sh\necho '[12:00:00] [Server thread/INFO]: Done (1.0s)! For help, type \"help\"'\nexec sleep 600\n";
+      let (mut manager, mut options, base) = fake_server("stop-timeout", script);
+      options.stop_timeout = Some(Duration::from_millis(500));
+      manager.start_with("1", 0, &options).unwrap();
+      manager.get("1").unwrap().wait_for_ready(Duration::from_secs(10)).unwrap();
+      let stopping = Instant::now();
+      match manager.stop("1") {
+        Err(Error::StopTimeout()) => {}
+        other => panic!("expected StopTimeout, got {:?}", other),
+      }
+      assert!(stopping.elapsed() < Duration::from_secs(5));
+      assert!(manager.get("1").is_none());
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn stop_returns_in_time_for_a_server_that_ignores_it() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let (mut manager, options, base) = fake_server("silent", "#!/bin/sh\nexec sleep 600\n");
+      let timeout = Duration::from_secs(1);
+      manager.set_stop_timeout(timeout);
+      manager.start_with("1", 0, &options).unwrap();
+      let pid = manager.get("1").unwrap().server_process.id();
+      //Nothing is printed, so the server never looks ready on its own
+      manager.force_ready("1").unwrap();
+      let stopping = Instant::now();
+      match manager.stop("1") {
+        Err(Error::StopTimeout()) => {}
+        other => panic!("expected StopTimeout, got {:?}", other),
+      }
+      assert!(stopping.elapsed() < timeout + Duration::from_secs(3));
+      assert!(manager.get("1").is_none());
+      //Safe as signal 0 only checks whether the process exists
+      assert_eq!(unsafe { libc::kill(pid as libc::pid_t, 0) }, -1);
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn captured_lines_match_or_time_out() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let script = r#"#!/bin/sh
+echo '[12:00:00] [Server thread/INFO]: Done (1.0s)! For help, type "help"'
+while read line; do
+  [ "$line" = seed ] && echo '[12:00:01] [Server thread/INFO]: Seed: [-4172144997902289642]'
+  [ "$line" = /stop ] && exit 0
+done
+"#;
+      let (mut manager, options, base) = fake_server("capture", script);
+      manager.start_with("1", 0, &options).unwrap();
+      let inst = manager.get("1").unwrap();
+      inst.wait_for_ready(Duration::from_secs(10)).unwrap();
+      let line = inst.send_and_capture("seed", |line| line.contains("Seed: "), Duration::from_secs(5)).unwrap();
+      assert_eq!(line, "[12:00:01] [Server thread/INFO]: Seed: [-4172144997902289642]");
+      let waiting = Instant::now();
+      match inst.send_and_capture("difficulty", |line| line.contains("The difficulty is"), Duration::from_millis(300)) {
+        Err(Error::CommandFailed(message)) => assert!(message.contains("difficulty")),
+        other => panic!("expected CommandFailed, got {:?}", other),
+      }
+      assert!(waiting.elapsed() >= Duration::from_millis(300));
+      manager.stop("1").unwrap();
+      fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn metrics_are_sampled_until_the_server_stops() {
+      let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+      let (mut manager, options, base) = fake_server("metrics", SCRIPT);
+      manager.start_with("1", 0, &options).unwrap();
+      manager.get("1").unwrap().wait_for_ready(Duration::from_secs(10)).unwrap();
+      let (sender, samples) = mpsc::channel();
+      let interval = Duration::from_millis(100);
+      let callback = Box::new(move |sample| sender.send(sample).unwrap());
+      let metrics = manager.start_metrics("1", interval, callback).unwrap();
+      let first: ServerMetrics = samples.recv_timeout(Duration::from_secs(5)).unwrap();
+      let second = samples.recv_timeout(Duration::from_secs(5)).unwrap();
+      assert!(second.uptime > first.uptime);
+      assert_eq!(second.players, 0);
+      assert_eq!(second.tps, None);
+      #[cfg(target_os = "linux")]
+      assert!(second.resources.is_some());
+      manager.stop("1").unwrap();
+      let deadline = Instant::now() + Duration::from_secs(5);
+      while metrics.is_running() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(50));
+      }
+      assert!(!metrics.is_running());
+      fs::remove_dir_all(&base).unwrap();
+    }
+  }
 }
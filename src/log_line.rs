@@ -0,0 +1,92 @@
+//! Parsing of the `[HH:MM:SS] [Thread/LEVEL]: message` lines Minecraft servers print.
+
+use chrono::NaiveTime;
+
+/// The severity of a console line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+  Info,
+  Warn,
+  /// Also used for FATAL lines
+  Error,
+  /// The line had no recognizable level, or one not listed here such as DEBUG
+  Unknown,
+}
+
+impl LogLevel {
+  fn from_name(name: &str) -> LogLevel {
+    match name {
+      "INFO" => LogLevel::Info,
+      "WARN" => LogLevel::Warn,
+      "ERROR" | "FATAL" => LogLevel::Error,
+      _ => LogLevel::Unknown,
+    }
+  }
+}
+
+/// A console line split into its parts.
+/// Obtained from [Instance::get_structured](struct.Instance.html#method.get_structured)
+///
+/// # Examples
+/// ```
+/// use serbo::{LogLevel, LogLine};
+///
+/// let line = LogLine::parse("[12:34:56] [Server thread/WARN]: Can't keep up!");
+/// assert_eq!(line.level, LogLevel::Warn);
+/// assert_eq!(line.thread, "Server thread");
+/// assert_eq!(line.message, "Can't keep up!");
+///
+/// let line = LogLine::parse("Loading libraries, please wait...");
+/// assert_eq!(line.level, LogLevel::Unknown);
+/// assert_eq!(line.timestamp, None);
+/// assert_eq!(line.message, "Loading libraries, please wait...");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+  /// The time of day the line was printed, in the server's local time
+  pub timestamp: Option<NaiveTime>,
+  /// The thread that printed the line, e.g. `Server thread`. Empty if the line wasn't recognized
+  pub thread: String,
+  pub level: LogLevel,
+  /// The text after the prefix, or the whole line if it wasn't recognized
+  pub message: String,
+  /// The line exactly as it was printed
+  pub raw: String,
+}
+
+impl LogLine {
+  /// Parses a console line
+  ///
+  /// # Remarks
+  /// Forge's extra `[logger]` tag before the message is skipped. Lines that don't follow the
+  /// format, such as JVM output or stack traces, keep their full text as the message with an
+  /// [Unknown](enum.LogLevel.html#variant.Unknown) level.
+  pub fn parse(line: &str) -> LogLine {
+    LogLine::parse_prefixed(line).unwrap_or_else(|| LogLine {
+      timestamp: None,
+      thread: String::new(),
+      level: LogLevel::Unknown,
+      message: line.to_string(),
+      raw: line.to_string(),
+    })
+  }
+  fn parse_prefixed(line: &str) -> Option<LogLine> {
+    let rest = line.strip_prefix('[')?;
+    let (time, rest) = rest.split_once("] [")?;
+    let mut parts = time.splitn(3, ':').map(|part| part.parse::<u32>().ok());
+    let timestamp = NaiveTime::from_hms_opt(parts.next()??, parts.next()??, parts.next()??)?;
+    let (source, rest) = rest.split_once(']')?;
+    let (thread, level) = source.rsplit_once('/')?;
+    let message = match rest.strip_prefix(": ") {
+      Some(message) => message,
+      None => rest.strip_prefix(" [")?.split_once("]: ")?.1,
+    };
+    Some(LogLine {
+      timestamp: Some(timestamp),
+      thread: thread.to_string(),
+      level: LogLevel::from_name(level),
+      message: message.to_string(),
+      raw: line.to_string(),
+    })
+  }
+}
@@ -0,0 +1,170 @@
+//! Periodic sampling of a server's uptime, players and resource use for monitoring.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::console::LogBuffer;
+use crate::resources::{self, ResourceUsage};
+use crate::ProcessExit;
+
+/// The reply to Paper and Spigot's `tps` command, followed by the 1, 5 and 15 minute averages
+const TPS_REPLY: &str = "TPS from last 1m, 5m, 15m:";
+
+/// One sample of a server's state, passed to the callback given to
+/// [start_metrics](struct.Manager.html#method.start_metrics)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServerMetrics {
+  /// How long ago the server process was launched, as in [uptime](struct.Instance.html#method.uptime)
+  pub uptime: Duration,
+  /// How many players are online, counted from the join and leave messages in the console like
+  /// [is_player_online](struct.Instance.html#method.is_player_online)
+  pub players: usize,
+  /// The memory the process is using and its CPU use since the previous sample, or None on
+  /// platforms where [resource_usage](struct.Instance.html#method.resource_usage) is unsupported
+  pub resources: Option<ResourceUsage>,
+  /// The most recent 1 minute TPS average the server printed, or None if it hasn't printed one.
+  /// Vanilla servers never do; Paper and Spigot print it in reply to their `tps` command
+  pub tps: Option<f32>,
+}
+
+/// The state of a server a sampler reads, shared with its [Instance](struct.Instance.html)
+pub(crate) struct Sampler {
+  pub(crate) pid: u32,
+  pub(crate) spawned: Instant,
+  pub(crate) exit: Arc<Mutex<ProcessExit>>,
+  pub(crate) alive: Arc<RwLock<bool>>,
+  pub(crate) stopping: Arc<RwLock<bool>>,
+  pub(crate) online_players: Arc<Mutex<HashSet<String>>>,
+  pub(crate) console_log: Arc<Mutex<LogBuffer>>,
+}
+
+impl Sampler {
+  fn active(&self, cancel: &RwLock<bool>) -> bool {
+    !*cancel.read().unwrap() && *self.alive.read().unwrap() && !*self.stopping.read().unwrap()
+  }
+  /// Reads the process' memory and CPU time, or None once it has exited or if it can't be read
+  fn process_usage(&self) -> Option<(Instant, u64, f64)> {
+    //Holding the lock keeps the process from being reaped, so its pid can't be reused meanwhile
+    let exit = self.exit.lock().unwrap();
+    if exit.exited {
+      return None;
+    }
+    let (memory_bytes, cpu_time) = resources::process_usage(self.pid).ok()?;
+    Some((Instant::now(), memory_bytes, cpu_time))
+  }
+}
+
+/// A running metrics sampler.
+/// Created by calling [start_metrics](struct.Manager.html#method.start_metrics) on a [Manager](struct.Manager.html)
+///
+/// Dropping the handle stops the sampler, waiting for a callback that is already running to return.
+#[derive(Debug)]
+pub struct MetricsHandle {
+  cancel: Arc<RwLock<bool>>,
+  sampler: Option<thread::JoinHandle<()>>,
+}
+
+impl MetricsHandle {
+  pub(crate) fn start(sampler: Sampler, interval: Duration, callback: Box<dyn Fn(ServerMetrics) + Send>) -> MetricsHandle {
+    let cancel = Arc::new(RwLock::new(false));
+    let cancel_sampler = cancel.clone();
+    let handle = thread::spawn(move || {
+      let mut previous = sampler.process_usage();
+      let mut checked = sampler.console_log.lock().unwrap().first_line();
+      let mut tps = None;
+      loop {
+        let deadline = Instant::now() + interval;
+        while Instant::now() < deadline {
+          if !sampler.active(&cancel_sampler) {
+            return;
+          }
+          thread::sleep(Duration::from_millis(50).min(interval));
+        }
+        if !sampler.active(&cancel_sampler) || sampler.exit.lock().unwrap().exited {
+          return;
+        }
+        let current = sampler.process_usage();
+        let resources = match (previous, current) {
+          (Some((before, _, cpu_before)), Some((now, memory_bytes, cpu_time))) => {
+            let elapsed = now.duration_since(before).as_secs_f64();
+            let cpu_percent = if elapsed > 0.0 {
+              ((cpu_time - cpu_before) / elapsed * 100.0) as f32
+            } else {
+              0.0
+            };
+            Some(ResourceUsage { memory_bytes, cpu_percent })
+          }
+          _ => None,
+        };
+        previous = current;
+        {
+          let log = sampler.console_log.lock().unwrap();
+          if let Some(latest) = log.lines_from(checked).filter_map(|line| parse_tps(line)).last() {
+            tps = Some(latest);
+          }
+          checked = log.end();
+        }
+        callback(ServerMetrics {
+          uptime: sampler.spawned.elapsed(),
+          players: sampler.online_players.lock().unwrap().len(),
+          resources,
+          tps,
+        });
+      }
+    });
+    MetricsHandle {
+      cancel,
+      sampler: Some(handle),
+    }
+  }
+  /// Returns whether the sampler is still running, i.e. it hasn't been stopped and the server
+  /// hasn't stopped
+  pub fn is_running(&self) -> bool {
+    match self.sampler {
+      Some(ref handle) => !handle.is_finished(),
+      None => false,
+    }
+  }
+  /// Stops the sampler, waiting for a callback that is already running to return
+  pub fn stop(self) {}
+}
+
+impl Drop for MetricsHandle {
+  fn drop(&mut self) {
+    *self.cancel.write().unwrap() = true;
+    if let Some(handle) = self.sampler.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+/// Reads the 1 minute average from a `tps` reply, dropping the colour codes and the `*` Paper
+/// puts in front of averages above 20
+fn parse_tps(line: &str) -> Option<f32> {
+  let averages = &line[line.find(TPS_REPLY)? + TPS_REPLY.len()..];
+  let mut plain = String::new();
+  let mut chars = averages.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '§' => {
+        chars.next();
+      }
+      _ => plain.push(c),
+    }
+  }
+  plain.split(',').next()?.trim().trim_start_matches('*').parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tps_is_read_from_the_tps_reply() {
+    assert_eq!(parse_tps("[12:00:00] [Server thread/INFO]: TPS from last 1m, 5m, 15m: 19.5, 19.98, 20.0"), Some(19.5));
+    assert_eq!(parse_tps("[12:00:00 INFO]: §6TPS from last 1m, 5m, 15m: §a*20.0, §a*20.0, §a*20.0"), Some(20.0));
+    assert_eq!(parse_tps("[12:00:00] [Server thread/INFO]: Unknown command. Type \"/help\" for help."), None);
+  }
+}
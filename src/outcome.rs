@@ -0,0 +1,54 @@
+//! A summary of an operation's result that can be sent to a frontend, e.g. as JSON.
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::Error;
+
+/// Whether an operation succeeded and, if not, why.
+/// Created from the Result of any Serbo operation
+///
+/// # Examples
+/// ```no_run
+/// let mut manager = serbo::Manager::new("servers", "versions");
+/// let outcome = serbo::OperationOutcome::from(&manager.start("1", 25565));
+/// if let Some(kind) = outcome.error_kind {
+///   println!("{}: {}", kind, outcome.error.unwrap());
+/// }
+/// ```
+/// # Remarks
+/// With the `serde` feature it implements `Serialize`, giving
+/// `{"success":false,"error":"Server is offline","error_kind":"ServerOffline"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct OperationOutcome {
+  /// Whether the operation succeeded
+  pub success: bool,
+  /// The error's message, if it failed
+  pub error: Option<String>,
+  /// The error's [kind](enum.Error.html#method.kind), if it failed
+  pub error_kind: Option<String>,
+}
+
+impl<T> From<&Result<T, Error>> for OperationOutcome {
+  fn from(result: &Result<T, Error>) -> Self {
+    match *result {
+      Ok(_) => OperationOutcome {
+        success: true,
+        error: None,
+        error_kind: None,
+      },
+      Err(ref e) => OperationOutcome {
+        success: false,
+        error: Some(e.to_string()),
+        error_kind: Some(e.kind().to_string()),
+      },
+    }
+  }
+}
+
+impl<T> From<Result<T, Error>> for OperationOutcome {
+  fn from(result: Result<T, Error>) -> Self {
+    OperationOutcome::from(&result)
+  }
+}
@@ -0,0 +1,45 @@
+//! The console patterns used to follow a server's lifecycle from its output.
+
+use regex::Regex;
+
+/// The regular expressions matched against each stdout line to detect changes in a server's state.
+/// Set with [Manager::set_console_patterns](struct.Manager.html#method.set_console_patterns)
+///
+/// The defaults match vanilla and Fabric servers. Every field can be replaced on its own.
+/// # Examples
+/// ```
+/// use regex::Regex;
+///
+/// let patterns = serbo::ConsolePatterns {
+///   ready: Regex::new("Server started").unwrap(),
+///   ..Default::default()
+/// };
+/// let mut manager = serbo::Manager::new("servers", "versions");
+/// manager.set_console_patterns(patterns);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConsolePatterns {
+  /// Marks the server as done starting. Defaults to the `Done (12.345s)! For help, type "help"` line
+  pub ready: Regex,
+  /// Marks the server as shutting down. Defaults to `Stopping the server` / `Stopping server`
+  pub stopping: Regex,
+  /// Identifies the JVM running out of memory. Defaults to `java.lang.OutOfMemoryError`
+  pub oom: Regex,
+  /// Identifies the server refusing to run without an accepted EULA.
+  /// Defaults to `You need to agree to the EULA in order to run the server`
+  pub eula: Regex,
+  /// Identifies the server failing to bind its port. Defaults to `FAILED TO BIND TO PORT`
+  pub port_fail: Regex,
+}
+
+impl Default for ConsolePatterns {
+  fn default() -> Self {
+    ConsolePatterns {
+      ready: Regex::new(r"Done \([0-9.,]+s\)! For help, type").unwrap(),
+      stopping: Regex::new(r"Stopping (the )?server").unwrap(),
+      oom: Regex::new(r"java\.lang\.OutOfMemoryError").unwrap(),
+      eula: Regex::new(r"You need to agree to the EULA in order to run the server").unwrap(),
+      port_fail: Regex::new(r"FAILED TO BIND TO PORT").unwrap(),
+    }
+  }
+}
@@ -0,0 +1,129 @@
+//! The Server List Ping, the status request the multiplayer screen sends to show a server's details.
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::{Error, Result};
+
+/// The protocol version sent in the handshake. -1 asks the server to answer whatever its version
+const HANDSHAKE_PROTOCOL: i32 = -1;
+/// The handshake's next state that asks for the status rather than a login
+const STATUS_STATE: i32 = 1;
+/// Status responses are a few kilobytes at most, even with a server icon
+const MAX_PACKET_LENGTH: i32 = 1 << 21;
+
+/// What a server reports about itself to the multiplayer screen.
+/// Obtained from [Instance::ping](struct.Instance.html#method.ping)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerPing {
+  /// The version name, e.g. `1.16.1`, or whatever the server software puts there
+  pub version: String,
+  /// The protocol number of the version
+  pub protocol: i32,
+  /// How many players are online
+  pub players_online: u32,
+  /// How many players can join, max-players in server.properties
+  pub players_max: u32,
+  /// The message of the day as plain text, with any formatting codes left in
+  pub motd: String,
+}
+
+/// Sends a status request to `host:port` and parses the reply
+pub(crate) fn ping(host: &str, port: u16, timeout: Duration) -> Result<ServerPing> {
+  let addr: SocketAddr = (host, port)
+    .to_socket_addrs()?
+    .next()
+    .ok_or_else(|| Error::IoError(io::Error::new(io::ErrorKind::NotFound, "no address for server host")))?;
+  let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+  stream.set_read_timeout(Some(timeout))?;
+  stream.set_write_timeout(Some(timeout))?;
+  let mut handshake = Vec::new();
+  write_varint(&mut handshake, 0x00);
+  write_varint(&mut handshake, HANDSHAKE_PROTOCOL);
+  write_varint(&mut handshake, host.len() as i32);
+  handshake.extend_from_slice(host.as_bytes());
+  handshake.extend_from_slice(&port.to_be_bytes());
+  write_varint(&mut handshake, STATUS_STATE);
+  write_packet(&mut stream, &handshake)?;
+  //The status request is an empty packet with id 0
+  write_packet(&mut stream, &[0x00])?;
+  let length = read_varint(&mut stream)?;
+  if length <= 0 || length > MAX_PACKET_LENGTH {
+    return Err(malformed());
+  }
+  let mut packet = vec![0; length as usize];
+  stream.read_exact(&mut packet)?;
+  let mut body = &packet[..];
+  if read_varint(&mut body)? != 0x00 {
+    return Err(malformed());
+  }
+  let json_length = read_varint(&mut body)?;
+  if json_length < 0 || json_length as usize > body.len() {
+    return Err(malformed());
+  }
+  let status: Value = serde_json::from_slice(&body[..json_length as usize]).map_err(|_| malformed())?;
+  Ok(ServerPing {
+    version: status["version"]["name"].as_str().unwrap_or_default().to_string(),
+    protocol: status["version"]["protocol"].as_i64().unwrap_or(0) as i32,
+    players_online: status["players"]["online"].as_u64().unwrap_or(0) as u32,
+    players_max: status["players"]["max"].as_u64().unwrap_or(0) as u32,
+    motd: chat_text(&status["description"]),
+  })
+}
+
+fn write_packet(stream: &mut TcpStream, body: &[u8]) -> io::Result<()> {
+  let mut packet = Vec::with_capacity(body.len() + 5);
+  write_varint(&mut packet, body.len() as i32);
+  packet.extend_from_slice(body);
+  stream.write_all(&packet)
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+  let mut value = value as u32;
+  loop {
+    if value & !0x7f == 0 {
+      buf.push(value as u8);
+      return;
+    }
+    buf.push((value & 0x7f) as u8 | 0x80);
+    value >>= 7;
+  }
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<i32> {
+  let mut value = 0u32;
+  for i in 0..5 {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    value |= u32::from(byte[0] & 0x7f) << (7 * i);
+    if byte[0] & 0x80 == 0 {
+      return Ok(value as i32);
+    }
+  }
+  Err(malformed())
+}
+
+/// Flattens a chat component, which older servers send as a plain string, into its text
+fn chat_text(component: &Value) -> String {
+  match component {
+    Value::String(text) => text.clone(),
+    Value::Object(_) => {
+      let mut text = component["text"].as_str().unwrap_or_default().to_string();
+      if let Some(extra) = component["extra"].as_array() {
+        for part in extra {
+          text.push_str(&chat_text(part));
+        }
+      }
+      text
+    }
+    Value::Array(parts) => parts.iter().map(chat_text).collect(),
+    _ => String::new(),
+  }
+}
+
+fn malformed() -> Error {
+  Error::IoError(io::Error::new(io::ErrorKind::InvalidData, "malformed server list ping response"))
+}
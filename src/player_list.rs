@@ -0,0 +1,54 @@
+//! Reading and editing of the JSON player lists a server keeps, such as whitelist.json and ops.json.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+/// A JSON array of player entries, each an object with at least a `name`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PlayerList {
+  entries: Vec<Value>,
+}
+
+impl PlayerList {
+  /// Reads the list at `path`, treating a missing file as an empty list
+  pub(crate) fn load(path: &Path) -> io::Result<PlayerList> {
+    let contents = match fs::read_to_string(path) {
+      Ok(contents) => contents,
+      Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(PlayerList::default()),
+      Err(e) => return Err(e),
+    };
+    match serde_json::from_str(&contents)? {
+      Value::Array(entries) => Ok(PlayerList { entries }),
+      _ => Err(io::Error::new(io::ErrorKind::InvalidData, "player list is not a JSON array")),
+    }
+  }
+  /// Writes the list back to `path`, indented like the server writes it
+  pub(crate) fn save(&self, path: &Path) -> io::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(&self.entries)?)
+  }
+  /// The names of every player on the list, in file order
+  pub(crate) fn names(&self) -> Vec<String> {
+    self
+      .entries
+      .iter()
+      .filter_map(|entry| entry.get("name").and_then(Value::as_str))
+      .map(String::from)
+      .collect()
+  }
+  /// Adds `entry`, replacing any entry for the same player. Names are matched ignoring case, as
+  /// the server does
+  pub(crate) fn insert(&mut self, name: &str, entry: Value) {
+    self.remove(name);
+    self.entries.push(entry);
+  }
+  /// Removes the entry for the player `name`, if there is one
+  pub(crate) fn remove(&mut self, name: &str) {
+    self.entries.retain(|entry| match entry.get("name").and_then(Value::as_str) {
+      Some(entry_name) => !entry_name.eq_ignore_ascii_case(name),
+      None => true,
+    });
+  }
+}
@@ -0,0 +1,332 @@
+//! Reading and editing of Java style `key=value` properties files, such as server.properties.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{Difficulty, Error, Gamemode, Result};
+
+#[derive(Debug, Clone)]
+enum Line {
+  Entry { key: String, value: String },
+  /// Comments, blank lines and anything else that isn't a key/value pair, kept verbatim
+  Other(String),
+}
+
+/// A properties file that keeps its comments, blank lines and key order when written back.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PropertiesFile {
+  lines: Vec<Line>,
+}
+
+impl PropertiesFile {
+  /// Parses the contents of a properties file, undoing the escapes java.util.Properties writes
+  pub(crate) fn parse(contents: &str) -> PropertiesFile {
+    let mut lines = Vec::new();
+    let mut natural_lines = contents.lines();
+    while let Some(line) = natural_lines.next() {
+      let trimmed = line.trim_start_matches(WHITESPACE);
+      if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+        lines.push(Line::Other(line.to_string()));
+        continue;
+      }
+      //A line ending in an odd number of backslashes carries on, minus its indent, on the next line
+      let mut logical = trimmed.to_string();
+      while logical.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1 {
+        logical.pop();
+        match natural_lines.next() {
+          Some(next) => logical.push_str(next.trim_start_matches(WHITESPACE)),
+          None => break,
+        }
+      }
+      let (key, value) = split_entry(&logical);
+      lines.push(Line::Entry {
+        key: unescape(key),
+        value: unescape(value),
+      });
+    }
+    PropertiesFile { lines }
+  }
+  /// Reads and parses the properties file at `path`, treating a missing file as empty
+  pub(crate) fn load(path: &Path) -> io::Result<PropertiesFile> {
+    match fs::read_to_string(path) {
+      Ok(contents) => Ok(PropertiesFile::parse(&contents)),
+      Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(PropertiesFile::default()),
+      Err(e) => Err(e),
+    }
+  }
+  /// Writes the properties back to `path`
+  pub(crate) fn save(&self, path: &Path) -> io::Result<()> {
+    fs::write(path, self.serialize())
+  }
+  /// Returns the value of `key`, if present
+  pub(crate) fn get(&self, key: &str) -> Option<&str> {
+    self.lines.iter().find_map(|line| match line {
+      Line::Entry { key: k, value } if k == key => Some(value.as_str()),
+      _ => None,
+    })
+  }
+  /// Iterates over every key and value, in file order
+  pub(crate) fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+    self.lines.iter().filter_map(|line| match line {
+      Line::Entry { key, value } => Some((key.as_str(), value.as_str())),
+      Line::Other(_) => None,
+    })
+  }
+  /// Sets `key` to `value`, replacing the existing entry in place or appending a new one
+  pub(crate) fn set(&mut self, key: &str, value: &str) {
+    for line in self.lines.iter_mut() {
+      if let Line::Entry { key: k, value: v } = line {
+        if k == key {
+          *v = value.to_string();
+          return;
+        }
+      }
+    }
+    self.lines.push(Line::Entry {
+      key: key.to_string(),
+      value: value.to_string(),
+    });
+  }
+  /// Renders the properties back into the file format, escaped the way java.util.Properties does
+  /// so a value can't break onto a line of its own
+  pub(crate) fn serialize(&self) -> String {
+    let mut out = String::new();
+    for line in &self.lines {
+      match line {
+        Line::Entry { key, value } => {
+          escape(&mut out, key, true);
+          out.push('=');
+          escape(&mut out, value, false);
+        }
+        Line::Other(text) => out.push_str(text),
+      }
+      out.push('\n');
+    }
+    out
+  }
+}
+
+/// The characters java.util.Properties treats as whitespace between and around keys and values
+const WHITESPACE: [char; 3] = [' ', '\t', '\x0c'];
+
+/// Splits a logical line at the first unescaped `=`, `:` or whitespace, into its raw key and value
+fn split_entry(line: &str) -> (&str, &str) {
+  let mut escaped = false;
+  let end = line
+    .char_indices()
+    .find(|&(_, c)| {
+      let separator = !escaped && (c == '=' || c == ':' || WHITESPACE.contains(&c));
+      escaped = !escaped && c == '\\';
+      separator
+    })
+    .map(|(index, _)| index);
+  let (key, rest) = match end {
+    Some(index) => line.split_at(index),
+    None => return (line, ""),
+  };
+  let rest = rest.trim_start_matches(WHITESPACE);
+  let rest = rest.strip_prefix(['=', ':']).unwrap_or(rest);
+  (key, rest.trim_start_matches(WHITESPACE))
+}
+
+/// Replaces the escapes in a raw key or value, including `\uXXXX` ones, with what they stand for
+fn unescape(text: &str) -> String {
+  let mut units: Vec<u16> = Vec::with_capacity(text.len());
+  let mut buf = [0; 2];
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    let c = match c {
+      '\\' => match chars.next() {
+        Some('t') => '\t',
+        Some('n') => '\n',
+        Some('r') => '\r',
+        Some('f') => '\x0c',
+        Some('u') => {
+          let hex: String = chars.clone().take(4).collect();
+          if hex.len() == 4 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            //Characters outside the BMP are written as two escaped surrogates, joined up below
+            units.push(u16::from_str_radix(&hex, 16).unwrap());
+            chars.nth(3);
+            continue;
+          }
+          //Java refuses malformed escapes, keep them as they are rather than losing the line
+          units.push(u16::from(b'\\'));
+          'u'
+        }
+        Some(other) => other,
+        None => break,
+      },
+      c => c,
+    };
+    units.extend_from_slice(c.encode_utf16(&mut buf));
+  }
+  String::from_utf16_lossy(&units)
+}
+
+/// Appends `text` to `out` with the escapes java.util.Properties uses. Anything outside printable
+/// ASCII becomes a `\uXXXX` escape, as the server reads the file as ISO 8859-1
+fn escape(out: &mut String, text: &str, is_key: bool) {
+  for (i, c) in text.chars().enumerate() {
+    match c {
+      '\\' => out.push_str("\\\\"),
+      '\t' => out.push_str("\\t"),
+      '\n' => out.push_str("\\n"),
+      '\r' => out.push_str("\\r"),
+      '\x0c' => out.push_str("\\f"),
+      '=' | ':' | '#' | '!' => {
+        out.push('\\');
+        out.push(c);
+      }
+      //Leading spaces of a value would be skipped when reading it back
+      ' ' if is_key || i == 0 => out.push_str("\\ "),
+      c if !(' '..='~').contains(&c) => {
+        for unit in c.encode_utf16(&mut [0; 2]) {
+          out.push_str(&format!("\\u{:04X}", unit));
+        }
+      }
+      c => out.push(c),
+    }
+  }
+}
+
+/// Parses `key` from the properties, falling back to `default` when it is absent or empty
+pub(crate) fn property<T: FromStr>(props: &PropertiesFile, key: &str, default: T) -> Result<T> {
+  match props.get(key).map(|value| value.trim()) {
+    None | Some("") => Ok(default),
+    Some(value) => value
+      .parse()
+      .map_err(|_| Error::InvalidProperty(key.to_string())),
+  }
+}
+
+/// The keys that [ServerProperties](struct.ServerProperties.html) stores in typed fields
+const TYPED_KEYS: [&str; 11] = [
+  "server-port",
+  "max-players",
+  "difficulty",
+  "gamemode",
+  "pvp",
+  "motd",
+  "online-mode",
+  "white-list",
+  "hardcore",
+  "view-distance",
+  "level-name",
+];
+
+/// The contents of a server.properties file, with commonly used settings as typed fields.
+/// Obtained from [Manager::get_properties](struct.Manager.html#method.get_properties)
+///
+/// Keys without a field of their own are kept, unparsed, in `raw`. Fields missing from the file
+/// hold the server's defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerProperties {
+  /// server-port
+  pub port: u16,
+  /// max-players
+  pub max_players: u32,
+  /// difficulty
+  pub difficulty: Difficulty,
+  /// gamemode, the default game mode for new players
+  pub gamemode: Gamemode,
+  /// pvp
+  pub pvp: bool,
+  /// motd, the message shown in the multiplayer server list
+  pub motd: String,
+  /// online-mode, whether players are authenticated with Mojang
+  pub online_mode: bool,
+  /// white-list
+  pub white_list: bool,
+  /// hardcore
+  pub hardcore: bool,
+  /// view-distance, in chunks
+  pub view_distance: u32,
+  /// level-name, the world folder to load
+  pub level_name: String,
+  /// Every other key in the file
+  pub raw: BTreeMap<String, String>,
+}
+
+impl ServerProperties {
+  pub(crate) fn from_file(file: &PropertiesFile) -> Result<ServerProperties> {
+    let raw = file
+      .entries()
+      .filter(|(key, _)| !TYPED_KEYS.contains(key))
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect();
+    Ok(ServerProperties {
+      port: property(file, "server-port", 25565)?,
+      max_players: property(file, "max-players", 20)?,
+      difficulty: property(file, "difficulty", Difficulty::Easy)?,
+      gamemode: property(file, "gamemode", Gamemode::Survival)?,
+      pvp: property(file, "pvp", true)?,
+      motd: file.get("motd").unwrap_or("A Minecraft Server").to_string(),
+      online_mode: property(file, "online-mode", true)?,
+      white_list: property(file, "white-list", false)?,
+      hardcore: property(file, "hardcore", false)?,
+      view_distance: property(file, "view-distance", 10)?,
+      level_name: property(file, "level-name", String::from("world"))?,
+      raw,
+    })
+  }
+  /// Writes every field into `file`, leaving its comments and key order alone
+  pub(crate) fn apply(&self, file: &mut PropertiesFile) {
+    file.set("server-port", &self.port.to_string());
+    file.set("max-players", &self.max_players.to_string());
+    file.set("difficulty", self.difficulty.as_str());
+    file.set("gamemode", self.gamemode.as_str());
+    file.set("pvp", &self.pvp.to_string());
+    file.set("motd", &self.motd);
+    file.set("online-mode", &self.online_mode.to_string());
+    file.set("white-list", &self.white_list.to_string());
+    file.set("hardcore", &self.hardcore.to_string());
+    file.set("view-distance", &self.view_distance.to_string());
+    file.set("level-name", &self.level_name);
+    for (key, value) in &self.raw {
+      file.set(key, value);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_java_escapes_and_continuations() {
+    let file = PropertiesFile::parse(concat!(
+      "#Minecraft server properties\n",
+      "motd=A \\u00A7bMinecraft\\: Server\\=1 \\\\o/\n",
+      "  level-name  :  my\\ world\n",
+      "resource-pack=https\\://example.com/\\\n",
+      "    pack.zip\n",
+      "spaced\\ key value\n",
+      "flag\n",
+    ));
+    assert_eq!(file.get("motd"), Some("A \u{a7}bMinecraft: Server=1 \\o/"));
+    assert_eq!(file.get("level-name"), Some("my world"));
+    assert_eq!(file.get("resource-pack"), Some("https://example.com/pack.zip"));
+    assert_eq!(file.get("spaced key"), Some("value"));
+    assert_eq!(file.get("flag"), Some(""));
+    assert_eq!(file.entries().count(), 5);
+  }
+
+  #[test]
+  fn values_are_escaped_on_write() {
+    let mut file = PropertiesFile::default();
+    file.set("motd", "Hi\nop-permission-level=4\r");
+    file.set("level-name", " wörld: \\ #1 \u{1F600}");
+    let written = file.serialize();
+    assert_eq!(
+      written,
+      "motd=Hi\\nop-permission-level\\=4\\r\nlevel-name=\\ w\\u00F6rld\\: \\\\ \\#1 \\uD83D\\uDE00\n"
+    );
+    let read = PropertiesFile::parse(&written);
+    assert_eq!(read.get("motd"), Some("Hi\nop-permission-level=4\r"));
+    assert_eq!(read.get("level-name"), Some(" wörld: \\ #1 \u{1F600}"));
+    assert_eq!(read.get("op-permission-level"), None);
+  }
+}
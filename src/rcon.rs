@@ -0,0 +1,94 @@
+//! A client for the Source RCON protocol, which Minecraft servers speak when enable-rcon is set.
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+const SERVERDATA_AUTH: i32 = 3;
+const SERVERDATA_AUTH_RESPONSE: i32 = 2;
+const SERVERDATA_EXECCOMMAND: i32 = 2;
+const SERVERDATA_RESPONSE_VALUE: i32 = 0;
+
+/// The request id used for every packet, the protocol only needs it to detect failed logins
+const REQUEST_ID: i32 = 1;
+
+/// An authenticated RCON connection
+#[derive(Debug)]
+pub(crate) struct RconClient {
+  stream: TcpStream,
+}
+
+impl RconClient {
+  /// Connects to `host:port` and logs in with `password`
+  pub(crate) fn connect(host: &str, port: u16, password: &str, timeout: Duration) -> Result<RconClient> {
+    let addr: SocketAddr = (host, port)
+      .to_socket_addrs()
+      .map_err(rcon_error)?
+      .next()
+      .ok_or_else(|| Error::IoError(io::Error::new(io::ErrorKind::NotFound, "no address for RCON host")))?;
+    let stream = TcpStream::connect_timeout(&addr, timeout).map_err(rcon_error)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let mut client = RconClient { stream };
+    client.write_packet(SERVERDATA_AUTH, password)?;
+    loop {
+      let (id, kind, _) = client.read_packet()?;
+      if kind == SERVERDATA_AUTH_RESPONSE {
+        if id == -1 {
+          return Err(Error::RconAuthFailed());
+        }
+        return Ok(client);
+      }
+    }
+  }
+  /// Runs a command and returns the server's reply
+  ///
+  /// Replies longer than one packet are cut off by the server, which only matters for very long
+  /// outputs such as the full help listing.
+  pub(crate) fn exec(&mut self, command: &str) -> Result<String> {
+    self.write_packet(SERVERDATA_EXECCOMMAND, command)?;
+    loop {
+      let (_, kind, body) = self.read_packet()?;
+      if kind == SERVERDATA_RESPONSE_VALUE {
+        return Ok(body);
+      }
+    }
+  }
+  fn write_packet(&mut self, kind: i32, body: &str) -> Result<()> {
+    let length = (4 + 4 + body.len() + 2) as i32;
+    let mut packet = Vec::with_capacity(length as usize + 4);
+    packet.extend_from_slice(&length.to_le_bytes());
+    packet.extend_from_slice(&REQUEST_ID.to_le_bytes());
+    packet.extend_from_slice(&kind.to_le_bytes());
+    packet.extend_from_slice(body.as_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    self.stream.write_all(&packet).map_err(rcon_error)
+  }
+  /// Reads one packet, returning its request id, type and body
+  fn read_packet(&mut self) -> Result<(i32, i32, String)> {
+    let mut header = [0; 12];
+    self.stream.read_exact(&mut header).map_err(rcon_error)?;
+    let length = i32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let id = i32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let kind = i32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    if !(10..=4106).contains(&length) {
+      return Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData, "malformed RCON packet")));
+    }
+    let mut body = vec![0; length as usize - 8];
+    self.stream.read_exact(&mut body).map_err(rcon_error)?;
+    //Strip the two null terminators
+    body.truncate(body.len() - 2);
+    Ok((id, kind, String::from_utf8_lossy(&body).into_owned()))
+  }
+}
+
+/// Maps connection failures to the RCON specific errors, so callers can tell them apart
+fn rcon_error(e: io::Error) -> Error {
+  match e.kind() {
+    io::ErrorKind::ConnectionRefused => Error::RconRefused(),
+    io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => Error::RconTimeout(),
+    _ => Error::IoError(e),
+  }
+}
@@ -0,0 +1,75 @@
+//! Memory and CPU usage of a server process, read from /proc on Linux.
+
+#[cfg(target_os = "linux")]
+use std::fs;
+
+use crate::{Error, Result};
+
+/// The resources a server process is using.
+/// Obtained from [Instance::resource_usage](struct.Instance.html#method.resource_usage)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceUsage {
+  /// The resident memory of the process, in bytes
+  pub memory_bytes: u64,
+  /// The CPU time used as a percentage of one core, so a busy server on several cores can exceed 100
+  pub cpu_percent: f32,
+}
+
+/// Reads a process' resident memory in bytes and the CPU time it has used in seconds
+#[cfg(target_os = "linux")]
+pub(crate) fn process_usage(pid: u32) -> Result<(u64, f64)> {
+  let memory_kb = parse_resident_kb(&fs::read_to_string(format!("/proc/{}/status", pid))?);
+  let ticks = match parse_cpu_ticks(&fs::read_to_string(format!("/proc/{}/stat", pid))?) {
+    Some(ticks) => ticks,
+    None => return Err(Error::Unsupported(String::from("resource_usage"))),
+  };
+  //Safe as sysconf only reads its argument
+  let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+  if ticks_per_second <= 0 {
+    return Err(Error::Unsupported(String::from("resource_usage")));
+  }
+  Ok((memory_kb * 1024, ticks as f64 / ticks_per_second as f64))
+}
+
+/// Reads the VmRSS line of a /proc/{pid}/status file, in kB. Zombies have none, so count as 0
+#[cfg(any(target_os = "linux", test))]
+fn parse_resident_kb(status: &str) -> u64 {
+  status
+    .lines()
+    .find_map(|line| line.strip_prefix("VmRSS:"))
+    .and_then(|value| value.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+    .unwrap_or(0)
+}
+
+/// Adds up the user and system clock ticks in a /proc/{pid}/stat file
+#[cfg(any(target_os = "linux", test))]
+fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+  //The command name can contain spaces, so the fields are counted from after it
+  let index = stat.rfind(')')?;
+  let fields: Vec<&str> = stat[index + 1..].split_whitespace().collect();
+  let utime = fields.get(11)?.parse::<u64>().ok()?;
+  let stime = fields.get(12)?.parse::<u64>().ok()?;
+  Some(utime + stime)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn process_usage(_pid: u32) -> Result<(u64, f64)> {
+  Err(Error::Unsupported(String::from("resource_usage")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn proc_files_are_parsed() {
+    let status = "Name:\tjava\nState:\tS (sleeping)\nVmPeak:\t 5120000 kB\nVmRSS:\t 1048576 kB\nThreads:\t41\n";
+    assert_eq!(parse_resident_kb(status), 1048576);
+    assert_eq!(parse_resident_kb("Name:\tjava\nState:\tZ (zombie)\n"), 0);
+    let stat = "4242 (java (server)) S 4200 4242 4200 0 -1 1077936384 181935 0 1 0 1520 311 0 0 20 0 41 0 \
+                8237910 5242880000 262144 18446744073709551615 1 1 0 0 0 0 0 2 16800975 0 0 0 17 3 0 0 0 0 0";
+    assert_eq!(parse_cpu_ticks(stat), Some(1520 + 311));
+    assert_eq!(parse_cpu_ticks("4242 (java) S 4200"), None);
+    assert_eq!(parse_cpu_ticks("garbage"), None);
+  }
+}
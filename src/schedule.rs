@@ -0,0 +1,211 @@
+//! Timers that restart servers and save their worlds at fixed intervals.
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::command_queue::CommandQueue;
+use crate::{Error, Manager, Result};
+
+/// How long before a scheduled restart players are warned, by default
+const DEFAULT_WARNINGS: [u64; 4] = [60, 30, 10, 5];
+
+/// Sets up scheduled restarts and autosaves for an online server.
+/// Created by calling [schedule](struct.Manager.html#method.schedule) on a [Manager](struct.Manager.html)
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// let mut manager = serbo::Manager::new("servers", "versions");
+/// manager.start("1", 25565).unwrap();
+/// manager
+///   .schedule("1")
+///   .restart_every(Duration::from_secs(6 * 60 * 60))
+///   .autosave_every(Duration::from_secs(15 * 60))
+///   .apply()
+///   .unwrap();
+/// loop {
+///   for (id, result) in manager.run_scheduled_restarts() {
+///     println!("Restarted {}: {:?}", id, result);
+///   }
+///   std::thread::sleep(Duration::from_secs(1));
+/// }
+/// ```
+pub struct ScheduleBuilder<'a> {
+  manager: &'a mut Manager,
+  id: String,
+  config: ScheduleConfig,
+}
+
+impl<'a> ScheduleBuilder<'a> {
+  pub(crate) fn new(manager: &'a mut Manager, id: &str) -> ScheduleBuilder<'a> {
+    ScheduleBuilder {
+      manager,
+      id: id.to_string(),
+      config: ScheduleConfig {
+        restart_every: None,
+        autosave_every: None,
+        warnings: DEFAULT_WARNINGS.iter().map(|&secs| Duration::from_secs(secs)).collect(),
+      },
+    }
+  }
+  /// Restarts the server each time `interval` has passed
+  pub fn restart_every(mut self, interval: Duration) -> Self {
+    self.config.restart_every = Some(interval);
+    self
+  }
+  /// Runs `save-all` each time `interval` has passed
+  pub fn autosave_every(mut self, interval: Duration) -> Self {
+    self.config.autosave_every = Some(interval);
+    self
+  }
+  /// Sets how long before each restart players are warned with `say`. Defaults to 60, 30, 10 and
+  /// 5 seconds. Warnings further ahead than the restart interval are skipped
+  pub fn warnings(mut self, before: &[Duration]) -> Self {
+    self.config.warnings = before.to_vec();
+    self
+  }
+  /// Starts the timers, replacing any schedule the server already had
+  ///
+  /// # Remarks
+  /// Returns [ServerOffline](enum.Error.html#variant.ServerOffline) if the server isn't running and
+  /// [InvalidArgument](enum.Error.html#variant.InvalidArgument) for a zero interval.
+  pub fn apply(self) -> Result<()> {
+    for interval in self.config.restart_every.iter().chain(self.config.autosave_every.iter()) {
+      if *interval == Duration::from_secs(0) {
+        return Err(Error::InvalidArgument(String::from("schedule intervals must be longer than zero")));
+      }
+    }
+    self.manager.apply_schedule(&self.id, self.config)
+  }
+}
+
+/// The intervals a schedule was set up with, kept so it can be set up again after a restart
+#[derive(Debug, Clone)]
+pub(crate) struct ScheduleConfig {
+  restart_every: Option<Duration>,
+  autosave_every: Option<Duration>,
+  warnings: Vec<Duration>,
+}
+
+/// The flags a timer thread checks to know whether it should keep going
+#[derive(Debug, Clone)]
+pub(crate) struct TimerSignals {
+  pub(crate) cancel: Arc<RwLock<bool>>,
+  pub(crate) alive: Arc<RwLock<bool>>,
+  pub(crate) stopping: Arc<RwLock<bool>>,
+}
+
+impl TimerSignals {
+  fn active(&self) -> bool {
+    !*self.cancel.read().unwrap() && *self.alive.read().unwrap() && !*self.stopping.read().unwrap()
+  }
+  /// Sleeps until `deadline`, returning false early if the timer should end
+  fn wait_until(&self, deadline: Instant) -> bool {
+    while Instant::now() < deadline {
+      if !self.active() {
+        return false;
+      }
+      thread::sleep(Duration::from_millis(50));
+    }
+    self.active()
+  }
+}
+
+/// The running timer threads of a server's schedule. Dropping it cancels them
+#[derive(Debug)]
+pub(crate) struct Schedule {
+  config: ScheduleConfig,
+  cancel: Arc<RwLock<bool>>,
+  next_restart: Option<SystemTime>,
+  next_autosave: Arc<Mutex<Option<SystemTime>>>,
+  restart_due: Arc<RwLock<bool>>,
+  threads: Vec<thread::JoinHandle<()>>,
+}
+
+impl Schedule {
+  /// Spawns the timers of `config`, which send their commands through `stdin_queue`
+  pub(crate) fn start(config: ScheduleConfig, stdin_queue: Arc<CommandQueue>, signals: TimerSignals) -> Schedule {
+    let mut schedule = Schedule {
+      cancel: signals.cancel.clone(),
+      next_restart: None,
+      next_autosave: Arc::new(Mutex::new(None)),
+      restart_due: Arc::new(RwLock::new(false)),
+      threads: Vec::new(),
+      config: config.clone(),
+    };
+    if let Some(interval) = config.autosave_every {
+      let mut deadline = Instant::now() + interval;
+      *schedule.next_autosave.lock().unwrap() = Some(SystemTime::now() + interval);
+      let next_autosave = schedule.next_autosave.clone();
+      let stdin_queue = stdin_queue.clone();
+      let signals = signals.clone();
+      schedule.threads.push(thread::spawn(move || loop {
+        if !signals.wait_until(deadline) {
+          return;
+        }
+        let _ = stdin_queue.push(String::from("save-all"));
+        deadline += interval;
+        *next_autosave.lock().unwrap() = Some(SystemTime::now() + interval);
+      }));
+    }
+    if let Some(interval) = config.restart_every {
+      let due = Instant::now() + interval;
+      schedule.next_restart = Some(SystemTime::now() + interval);
+      let restart_due = schedule.restart_due.clone();
+      let mut warnings: Vec<Duration> = config.warnings.iter().filter(|&&w| w < interval).cloned().collect();
+      //Furthest ahead first, as that is the order they come due in
+      warnings.sort_by(|a, b| b.cmp(a));
+      schedule.threads.push(thread::spawn(move || {
+        for warning in warnings {
+          if !signals.wait_until(due - warning) {
+            return;
+          }
+          let _ = stdin_queue.push(format!("say Server restarting in {}", describe(warning)));
+        }
+        if !signals.wait_until(due) {
+          return;
+        }
+        let _ = stdin_queue.push(String::from("save-all"));
+        *restart_due.write().unwrap() = true;
+      }));
+    }
+    schedule
+  }
+  pub(crate) fn config(&self) -> &ScheduleConfig {
+    &self.config
+  }
+  /// Whether the restart time has been reached, and the server is waiting to be restarted
+  pub(crate) fn restart_due(&self) -> bool {
+    *self.restart_due.read().unwrap()
+  }
+  pub(crate) fn next_restart(&self) -> Option<SystemTime> {
+    match self.restart_due() {
+      true => None,
+      false => self.next_restart,
+    }
+  }
+  pub(crate) fn next_autosave(&self) -> Option<SystemTime> {
+    *self.next_autosave.lock().unwrap()
+  }
+}
+
+impl Drop for Schedule {
+  fn drop(&mut self) {
+    *self.cancel.write().unwrap() = true;
+    for handle in self.threads.drain(..) {
+      let _ = handle.join();
+    }
+  }
+}
+
+/// Puts a warning offset into words for the countdown, e.g. `1 minute` or `30 seconds`
+fn describe(duration: Duration) -> String {
+  let secs = duration.as_secs();
+  match secs {
+    60 => String::from("1 minute"),
+    1 => String::from("1 second"),
+    s if s > 60 && s % 60 == 0 => format!("{} minutes", s / 60),
+    s => format!("{} seconds", s),
+  }
+}